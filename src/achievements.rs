@@ -1,6 +1,7 @@
 use {
     crate::*,
     anyhow::anyhow,
+    chrono::NaiveDate,
     fnv::FnvHasher,
     std::{
         cmp::Reverse,
@@ -10,9 +11,24 @@ use {
             Hasher,
         },
         path::PathBuf,
+        time::Duration,
     },
 };
 
+/// Return the directory where mazter keeps achievements and replays
+pub fn data_dir() -> anyhow::Result<PathBuf> {
+    let project_dirs = directories::ProjectDirs::from("org", "dystroy", "mazter")
+        .ok_or_else(|| anyhow!("no conf directory"))?;
+    Ok(project_dirs.data_dir().to_path_buf())
+}
+
+/// Return the directory where mazter looks for its configuration
+pub fn config_dir() -> anyhow::Result<PathBuf> {
+    let project_dirs = directories::ProjectDirs::from("org", "dystroy", "mazter")
+        .ok_or_else(|| anyhow!("no conf directory"))?;
+    Ok(project_dirs.config_dir().to_path_buf())
+}
+
 /// Must be changed when the rules change so that all levels are considered
 /// not done. It's not necessary to change it when specs changed because they're
 /// hashed in the record.
@@ -41,11 +57,64 @@ impl<'s> Achievement<'s> {
     }
 }
 
+/// How a level was won
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    /// Number of turns played, waits included
+    pub turns: usize,
+    /// Number of moves of the shortest way from the start to the exit
+    pub optimal: usize,
+    pub duration: Duration,
+    pub lives: i32,
+    pub potions: usize,
+    pub hints: usize,
+    /// The period of the clock, in milliseconds, if the game was timed
+    pub period: Option<u64>,
+}
+
+impl Score {
+    /// Compute the score of a won maze, given the time the game took
+    pub fn new(
+        maze: &Maze,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            turns: maze.turn(),
+            optimal: maze.optimal_moves().unwrap_or(0),
+            duration,
+            lives: maze.lives,
+            potions: maze.potions_drunk(),
+            hints: maze.hints_used(),
+            period: maze.clock().map(|clock| clock.period),
+        }
+    }
+    /// Number of moves which weren't necessary
+    pub fn extra_turns(&self) -> usize {
+        self.turns.saturating_sub(self.optimal)
+    }
+}
+
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct Record {
     pub user: String,
     pub level: usize,
     hash: u64,
+    // the score, missing in records of older versions
+    #[serde(default)]
+    turns: Option<usize>,
+    #[serde(default)]
+    optimal: Option<usize>,
+    #[serde(default)]
+    millis: Option<u64>,
+    #[serde(default)]
+    lives: Option<i32>,
+    #[serde(default)]
+    potions: Option<usize>,
+    #[serde(default)]
+    hints: Option<usize>,
+    /// The period of the clock, in milliseconds, for wins of timed games
+    #[serde(default)]
+    period: Option<u64>,
 }
 
 impl<'s> From<Achievement<'s>> for Record {
@@ -53,34 +122,120 @@ impl<'s> From<Achievement<'s>> for Record {
         let level = ach.level;
         let user = ach.user.to_string();
         let hash = ach.hash();
-        Self { user, level, hash }
+        Self {
+            user,
+            level,
+            hash,
+            turns: None,
+            optimal: None,
+            millis: None,
+            lives: None,
+            potions: None,
+            hints: None,
+            period: None,
+        }
     }
 }
 
 impl Record {
+    fn with_score(
+        mut self,
+        score: Score,
+    ) -> Self {
+        self.turns = Some(score.turns);
+        self.optimal = Some(score.optimal);
+        self.millis = Some(score.duration.as_millis() as u64);
+        self.lives = Some(score.lives);
+        self.potions = Some(score.potions);
+        self.hints = Some(score.hints);
+        self.period = score.period;
+        self
+    }
     pub fn achievement(&self) -> Achievement<'_> {
         Achievement::new(&self.user, self.level)
     }
     pub fn is_valid(&self) -> bool {
         self.hash == self.achievement().hash()
     }
+    pub fn score(&self) -> Option<Score> {
+        Some(Score {
+            turns: self.turns?,
+            optimal: self.optimal?,
+            duration: Duration::from_millis(self.millis?),
+            lives: self.lives?,
+            potions: self.potions?,
+            // there was no hint before they were recorded
+            hints: self.hints.unwrap_or(0),
+            period: self.period,
+        })
+    }
+}
+
+/// A win of the daily challenge
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct DailyRecord {
+    pub user: String,
+    /// The date of the challenge, as `YYYY-MM-DD`
+    pub date: String,
+    turns: usize,
+    optimal: usize,
+    millis: u64,
+    lives: i32,
+    potions: usize,
+    hints: usize,
+    #[serde(default)]
+    period: Option<u64>,
+}
+
+impl DailyRecord {
+    pub fn new(
+        user: &str,
+        date: NaiveDate,
+        score: Score,
+    ) -> Self {
+        Self {
+            user: user.to_string(),
+            date: date.to_string(),
+            turns: score.turns,
+            optimal: score.optimal,
+            millis: score.duration.as_millis() as u64,
+            lives: score.lives,
+            potions: score.potions,
+            hints: score.hints,
+            period: score.period,
+        }
+    }
+    pub fn score(&self) -> Score {
+        Score {
+            turns: self.turns,
+            optimal: self.optimal,
+            duration: Duration::from_millis(self.millis),
+            lives: self.lives,
+            potions: self.potions,
+            hints: self.hints,
+            period: self.period,
+        }
+    }
 }
 
 /// Achievement Database
 ///
 /// It's designed to ensure a level you achieved stays achieved
 /// on upgrading mazter unless the level specifications changed
-/// with the upgrade
+/// with the upgrade.
+///
+/// The wins of daily challenges, which aren't levels, are kept apart,
+/// in their own leaderboard.
 pub struct Database {
     file_path: PathBuf,
     records: Vec<Record>,
+    daily_file_path: PathBuf,
+    daily_records: Vec<DailyRecord>,
 }
 
 impl Database {
     fn new() -> anyhow::Result<Self> {
-        let project_dirs = directories::ProjectDirs::from("org", "dystroy", "mazter")
-            .ok_or_else(|| anyhow!("no conf directory"))?;
-        let file_path = project_dirs.data_dir().join("achievements.csv");
+        let file_path = data_dir()?.join("achievements.csv");
         debug!("file_path: {:?}", &file_path);
         let mut records = Vec::new();
         if file_path.exists() {
@@ -96,13 +251,33 @@ impl Database {
                 }
             }
         }
-        Ok(Self { file_path, records })
+        let daily_file_path = data_dir()?.join("daily.csv");
+        let mut daily_records = Vec::new();
+        if daily_file_path.exists() {
+            let mut csv_reader = csv::Reader::from_path(&daily_file_path)?;
+            daily_records.extend(
+                csv_reader
+                    .deserialize::<DailyRecord>()
+                    .filter_map(Result::ok),
+            );
+        }
+        Ok(Self {
+            file_path,
+            records,
+            daily_file_path,
+            daily_records,
+        })
     }
     fn add(
         &mut self,
         ach: Achievement,
+        score: Option<Score>,
     ) {
-        self.records.push(ach.into());
+        let record = Record::from(ach);
+        self.records.push(match score {
+            Some(score) => record.with_score(score),
+            None => record,
+        });
     }
     fn write(&self) -> anyhow::Result<()> {
         fs::create_dir_all(
@@ -115,6 +290,13 @@ impl Database {
             writer.serialize(record)?;
         }
         writer.flush()?;
+        if !self.daily_records.is_empty() {
+            let mut writer = csv::Writer::from_path(&self.daily_file_path)?;
+            for record in &self.daily_records {
+                writer.serialize(record)?;
+            }
+            writer.flush()?;
+        }
         Ok(())
     }
     fn contains(
@@ -128,15 +310,18 @@ impl Database {
 
     pub fn save(ach: Achievement) -> anyhow::Result<()> {
         let mut db = Self::new()?;
-        db.add(ach);
+        db.add(ach, None);
         db.write()?;
         Ok(())
     }
-    /// save the achievement and return the first following
-    /// level not won
-    pub fn advance(ach: Achievement) -> anyhow::Result<usize> {
+    /// save the achievement, with its score, and return the first
+    /// following level not won
+    pub fn advance(
+        ach: Achievement,
+        score: Option<Score>,
+    ) -> anyhow::Result<usize> {
         let mut db = Self::new()?;
-        db.add(ach);
+        db.add(ach, score);
         db.write()?;
         let mut level = ach.level + 1;
         loop {
@@ -206,6 +391,57 @@ impl Database {
         }
         Ok(())
     }
+    /// Save a win of the daily challenge
+    pub fn add_daily(
+        user: &str,
+        date: NaiveDate,
+        score: Score,
+    ) -> anyhow::Result<()> {
+        let mut db = Self::new()?;
+        db.daily_records.push(DailyRecord::new(user, date, score));
+        db.write()
+    }
+    /// Return the scores of all the wins of the daily challenge of a date
+    pub fn daily_scores(date: NaiveDate) -> anyhow::Result<Vec<(String, Score)>> {
+        let db = Self::new()?;
+        let date = date.to_string();
+        Ok(db
+            .daily_records
+            .into_iter()
+            .filter(|record| record.date == date)
+            .map(|record| {
+                let score = record.score();
+                (record.user, score)
+            })
+            .collect())
+    }
+    /// Return the scores of all the wins of a level
+    pub fn level_scores(level: usize) -> anyhow::Result<Vec<(String, Score)>> {
+        let db = Self::new()?;
+        Ok(db
+            .records
+            .into_iter()
+            .filter(|record| record.level == level)
+            .filter_map(|record| record.score().map(|score| (record.user, score)))
+            .collect())
+    }
+    /// Tell whether the score, not yet saved, is better, in moves or in
+    /// time, than all the previous scores of the user on the level, with
+    /// the same clock
+    pub fn is_personal_best(
+        ach: Achievement,
+        score: &Score,
+    ) -> anyhow::Result<bool> {
+        let previous: Vec<Score> = Self::level_scores(ach.level)?
+            .into_iter()
+            .filter(|(user, previous)| user == ach.user && previous.period == score.period)
+            .map(|(_, score)| score)
+            .collect();
+        let best_turns = previous.iter().map(|s| s.turns).min();
+        let best_duration = previous.iter().map(|s| s.duration).min();
+        Ok(best_turns.map_or(false, |turns| score.turns < turns)
+            || best_duration.map_or(false, |duration| score.duration < duration))
+    }
     pub fn hof() -> anyhow::Result<Vec<Record>> {
         let mut db = Self::new()?;
         let mut hof: Vec<Record> = Vec::new();
@@ -0,0 +1,39 @@
+use crate::*;
+
+/// Something the player, or the bot playing for them, may do
+/// during a turn.
+///
+/// Actions are independent of the terminal, so that games can be
+/// played without one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Action {
+    Move(Dir),
+    /// Let the world move without moving
+    Wait,
+    GiveUp,
+    /// Let the bot choose the move
+    Auto,
+    /// Show the next rooms of the way, without ending the turn
+    Hint,
+    /// Let the world move on its own, in timed games
+    Tick,
+}
+
+impl Action {
+    pub fn apply(
+        self,
+        maze: &mut Maze,
+        events: &mut EventList,
+    ) {
+        match self {
+            Self::Move(dir) => maze.try_move(dir, events),
+            Self::Wait => maze.wait(events),
+            Self::GiveUp => maze.give_up(),
+            Self::Auto => maze.move_player_auto(events),
+            Self::Hint => {
+                maze.give_hint();
+            }
+            Self::Tick => maze.end_player_turn(events),
+        }
+    }
+}
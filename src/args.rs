@@ -1,3 +1,8 @@
+use {
+    crate::*,
+    std::path::PathBuf,
+};
+
 #[derive(Debug, clap::Parser)]
 #[clap(
     author,
@@ -13,18 +18,94 @@ pub struct Args {
     #[clap(long, value_parser)]
     pub reset: bool,
 
-    /// print the Hall of Fame
+    /// print the Hall of Fame, or the best scores of a level with --level,
+    /// or of the daily challenge with --daily
     #[clap(long, value_parser)]
     pub hof: bool,
 
+    /// how players are ranked by `--hof --level` and `--hof --daily`:
+    /// moves or time
+    #[clap(long, value_parser, default_value = "moves")]
+    pub rank_by: hof::Ranking,
+
     /// level to play or print - default is the first not won
     #[clap(long, value_parser)]
     pub level: Option<usize>,
 
+    /// play, or build, or print the best scores of, the maze of the day,
+    /// the same for everyone
+    #[clap(long, value_parser)]
+    pub daily: bool,
+
+    /// seed of the random generation, to get the same maze again
+    #[clap(long, value_parser)]
+    pub seed: Option<u64>,
+
+    /// algorithm of the built maze: growing-tree, prim, kruskal,
+    /// wilson, division or eller
+    #[clap(long, value_parser)]
+    pub algorithm: Option<Algorithm>,
+
+    /// shape of the built maze: rectangle, disk, ring, heart, diamond,
+    /// cross, banner:<text>, or the path to a text file where '#' means inside
+    #[clap(long, value_parser)]
+    pub shape: Option<String>,
+
+    /// play, or build, the maze described in a JSON or text file
+    #[clap(long, value_parser)]
+    pub load: Option<PathBuf>,
+
+    /// play back a game recorded in the replays directory
+    #[clap(long, value_parser)]
+    pub replay: Option<PathBuf>,
+
+    /// output format of the built maze: terminal, svg, json or text
+    #[clap(long, value_parser, default_value = "terminal")]
+    pub format: Format,
+
+    /// show the path from start to exit in the built maze
+    #[clap(long, value_parser)]
+    pub solution: bool,
+
+    /// let the bot play the given levels (eg `12` or `10-20`) and
+    /// print statistics, to measure their difficulty
+    #[clap(long, value_parser)]
+    pub simulate: Option<LevelRange>,
+
+    /// number of games played per level by --simulate
+    #[clap(long, value_parser, default_value_t = 10)]
+    pub runs: usize,
+
+    /// print the statistics of --simulate as CSV
+    #[clap(long, value_parser)]
+    pub csv: bool,
+
+    /// play against the clock: the monsters move every given number of
+    /// milliseconds, whatever you do, and the time is limited - with
+    /// --hof, show the wins of timed games of this period
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..))]
+    pub timed: Option<u64>,
+
     /// number of levels to play
     #[clap(long, value_parser)]
     pub levels: Option<usize>,
 
+    /// colors: dark, light, solarized, high-contrast, or the path to a
+    /// TOML theme file - default is the theme.toml file of the config
+    /// directory, if any
+    #[clap(long, value_parser)]
+    pub theme: Option<String>,
+
+    /// characters of the maze: blocks, ascii (without any color), box, or
+    /// braille to see huge mazes whole
+    #[clap(long, value_parser, default_value = "blocks")]
+    pub charset: Charset,
+
+    /// keys: arrows, vim, wasd, or the path to a TOML keymap file -
+    /// default is the keys.toml file of the config directory, if any
+    #[clap(long, value_parser)]
+    pub keys: Option<String>,
+
     /// user playing
     #[clap(short, long, value_parser, default_value_t = whoami::username())]
     pub user: String,
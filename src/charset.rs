@@ -0,0 +1,177 @@
+use {
+    crate::*,
+    std::{
+        fmt,
+        str::FromStr,
+    },
+};
+
+/// The characters mazes are drawn with in the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Charset {
+    /// Colored block characters, two maze rows per line when needed
+    #[default]
+    Blocks,
+    /// Plain ASCII, without any color
+    Ascii,
+    /// Box-drawing lines for walls, and colored letters
+    Box,
+    /// Braille characters, each one showing eight positions, so that
+    /// even huge mazes fit in the screen
+    Braille,
+}
+
+pub static CHARSETS: &[Charset] = &[
+    Charset::Blocks,
+    Charset::Ascii,
+    Charset::Box,
+    Charset::Braille,
+];
+
+// box-drawing characters, indexed by the walls around: 1 for up,
+// 2 for right, 4 for down, 8 for left
+static BOX_WALLS: [char; 16] = [
+    '•', '╵', '╶', '└', '╷', '│', '┌', '├', '╴', '┘', '─', '┴', '┐', '┤', '┬', '┼',
+];
+
+// bits of the dots of a braille character, indexed by [y][x] in its
+// block of 2 columns and 4 rows
+static BRAILLE_DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+/// Return the braille character showing the block of 2x4 positions whose
+/// top left one is given, and the nature which should give its color: a
+/// braille character has only one color, so the most important nature wins.
+///
+/// Return None when there's nothing to draw in the block
+pub fn braille_cell(
+    maze: &Maze,
+    top_left: Pos,
+) -> Option<(char, Nature)> {
+    let mut bits = 0;
+    let mut main_nature: Option<Nature> = None;
+    for (dy, row) in BRAILLE_DOTS.iter().enumerate() {
+        for (dx, bit) in row.iter().enumerate() {
+            let pos = Pos::new(top_left.x + dx, top_left.y + dy);
+            if pos.x >= maze.dim.w || pos.y >= maze.dim.h {
+                continue;
+            }
+            let nature = maze.visible_nature(pos);
+            let importance = braille_importance(nature);
+            if importance == 0 {
+                continue;
+            }
+            bits |= bit;
+            if main_nature.map_or(true, |n| braille_importance(n) < importance) {
+                main_nature = Some(nature);
+            }
+        }
+    }
+    let c = char::from_u32(0x2800 + bits)?;
+    main_nature.map(|nature| (c, nature))
+}
+
+/// How much a nature matters when choosing the color of a braille
+/// character, 0 for natures which aren't drawn as dots
+fn braille_importance(nature: Nature) -> usize {
+    match nature {
+        Nature::Room
+        | Nature::InvisibleWall
+        | Nature::Unknown
+        | Nature::Remembered
+        | Nature::Visited => 0,
+        Nature::Wall => 1,
+        Nature::Highlight => 2,
+        Nature::Potion | Nature::Door | Nature::Key => 3,
+        Nature::Monster(_) => 4,
+        Nature::Player => 5,
+    }
+}
+
+impl Charset {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Blocks => "blocks",
+            Self::Ascii => "ascii",
+            Self::Box => "box",
+            Self::Braille => "braille",
+        }
+    }
+    /// Tell whether a maze position is drawn as one character, instead of
+    /// block or braille characters
+    pub fn is_text(self) -> bool {
+        matches!(self, Self::Ascii | Self::Box)
+    }
+    pub fn is_colored(self) -> bool {
+        self != Self::Ascii
+    }
+    /// Return the character of a maze position, for the text charsets
+    pub fn cell_char(
+        self,
+        maze: &Maze,
+        pos: Pos,
+    ) -> char {
+        match maze.visible_nature(pos) {
+            Nature::Wall if self == Self::Box => {
+                let is_wall =
+                    |p: Option<Pos>| p.map_or(false, |p| maze.visible_nature(p) == Nature::Wall);
+                let mut idx = 0;
+                for (bit, dir) in [
+                    (1, Dir::Up),
+                    (2, Dir::Right),
+                    (4, Dir::Down),
+                    (8, Dir::Left),
+                ] {
+                    if is_wall(maze.pos_in_dir(pos, dir)) {
+                        idx |= bit;
+                    }
+                }
+                BOX_WALLS[idx]
+            }
+            Nature::Wall => '#',
+            Nature::Room if self == Self::Box => ' ',
+            Nature::Room => '.',
+            Nature::InvisibleWall | Nature::Unknown => ' ',
+            Nature::Remembered => ',',
+            Nature::Visited if self == Self::Box => '·',
+            Nature::Visited => ':',
+            Nature::Player => '@',
+            Nature::Monster(kind) => match kind {
+                MonsterKind::Hunter => 'M',
+                MonsterKind::Slow => 'm',
+                MonsterKind::Wanderer => 'W',
+                MonsterKind::Guard => 'G',
+                MonsterKind::Ambusher => 'A',
+            },
+            Nature::Potion => '+',
+            Nature::Highlight => '*',
+            Nature::Door => 'D',
+            Nature::Key => 'K',
+        }
+    }
+}
+
+impl fmt::Display for Charset {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Charset {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CHARSETS
+            .iter()
+            .find(|c| c.name() == s)
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = CHARSETS.iter().map(|c| c.name()).collect();
+                format!(
+                    "unknown charset {s:?}, expected one of {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
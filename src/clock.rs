@@ -0,0 +1,39 @@
+use {
+    crate::*,
+    std::time::Duration,
+};
+
+/// The clock of a timed game, where the world moves on its own
+/// instead of after every move of the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Clock {
+    /// The time between two moves of the world, in milliseconds
+    pub period: u64,
+    /// The number of moves of the world after which the level is lost
+    pub limit: usize,
+}
+
+impl Clock {
+    /// Build the clock of a maze, with a time limit leaving room for
+    /// detours to the keys and around the monsters
+    pub fn new(
+        maze: &Maze,
+        period: u64,
+    ) -> Self {
+        let optimal = maze.optimal_moves().unwrap_or(maze.dim.w + maze.dim.h);
+        Self {
+            period,
+            limit: 2 * optimal + 30,
+        }
+    }
+    pub fn period(self) -> Duration {
+        Duration::from_millis(self.period)
+    }
+    /// The time left before the level is lost, after the given turn
+    pub fn remaining(
+        self,
+        turn: usize,
+    ) -> Duration {
+        self.period() * self.limit.saturating_sub(turn) as u32
+    }
+}
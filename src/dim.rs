@@ -1,12 +1,8 @@
-use {
-    crate::*,
-    std::io,
-    termimad::crossterm::terminal,
-};
+use crate::*;
 
 /// a couple of usize intended as dimensions
 /// (screen, maze, etc.)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct Dim {
     pub w: usize,
     pub h: usize,
@@ -18,9 +14,10 @@ impl Dim {
     ) -> Self {
         Self { w, h }
     }
-    pub fn terminal() -> io::Result<Self> {
+    #[cfg(feature = "tui")]
+    pub fn terminal() -> std::io::Result<Self> {
         #[allow(unused_mut)]
-        let (mut width, mut height) = terminal::size()?;
+        let (mut width, mut height) = termimad::crossterm::terminal::size()?;
         #[cfg(windows)]
         {
             width -= 1;
@@ -62,6 +62,7 @@ impl EventList {
         start: Pos,
         dir: Dir,
         mut dest_background_nature: Nature,
+        kind: MonsterKind,
     ) {
         let Some(dest) = start.in_dir(dir) else {
             return;
@@ -76,7 +77,7 @@ impl EventList {
                 }
             }
         }
-        let moving_nature = Nature::Monster;
+        let moving_nature = Nature::Monster(kind);
         let start_background_nature = Nature::Room;
         self.events.push(Event::Move(PosMove {
             start,
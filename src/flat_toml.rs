@@ -0,0 +1,27 @@
+use {
+    anyhow::bail,
+    toml::Value,
+};
+
+/// A `name = value` entry of a configuration file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlatTomlEntry {
+    pub name: String,
+    pub value: String,
+}
+
+/// Read a TOML file made only of `name = value` entries, in order, the
+/// values being strings or integers
+pub fn parse_flat_toml(toml: &str) -> anyhow::Result<Vec<FlatTomlEntry>> {
+    let table: toml::Table = toml::from_str(toml)?;
+    let mut entries = Vec::new();
+    for (name, value) in table {
+        let value = match value {
+            Value::String(s) => s,
+            Value::Integer(i) => i.to_string(),
+            _ => bail!("The value of {name:?} must be a string"),
+        };
+        entries.push(FlatTomlEntry { name, value });
+    }
+    Ok(entries)
+}
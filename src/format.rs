@@ -0,0 +1,54 @@
+use std::{
+    fmt,
+    str::FromStr,
+};
+
+/// The output format of a built maze
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Colored characters, for the terminal
+    #[default]
+    Terminal,
+    /// A vector image
+    Svg,
+    /// The description of the maze, for other programs or for `--load`
+    Json,
+    /// A grid of characters, easy to edit
+    Text,
+}
+
+pub static FORMATS: &[Format] = &[Format::Terminal, Format::Svg, Format::Json, Format::Text];
+
+impl Format {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Terminal => "terminal",
+            Self::Svg => "svg",
+            Self::Json => "json",
+            Self::Text => "text",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FORMATS
+            .iter()
+            .find(|f| f.name() == s)
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = FORMATS.iter().map(|f| f.name()).collect();
+                format!("unknown format {s:?}, expected one of {}", names.join(", "))
+            })
+    }
+}
@@ -0,0 +1,285 @@
+mod division;
+mod eller;
+mod growing_tree;
+mod kruskal;
+mod prim;
+mod wilson;
+
+pub use {
+    division::*,
+    eller::*,
+    growing_tree::*,
+    kruskal::*,
+    prim::*,
+    wilson::*,
+};
+
+use {
+    crate::*,
+    rand::Rng,
+    smallvec::SmallVec,
+    std::{
+        fmt,
+        str::FromStr,
+    },
+};
+
+/// A maze generation algorithm, carving the rooms of a maze
+/// whose start is already set
+pub trait Generator {
+    /// Carve the rooms.
+    ///
+    /// When `fill` is false, the generator may stop as soon as an exit
+    /// can be placed (not all generators can stop before the end).
+    fn carve(
+        &mut self,
+        maze: &mut Maze,
+        fill: bool,
+    );
+}
+
+/// The available maze generation algorithms
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// The historical mazter algorithm, mostly long corridors
+    GrowingTree,
+    /// Randomized Prim, many short dead ends
+    Prim,
+    /// Randomized Kruskal, many short dead ends, no privileged direction
+    Kruskal,
+    /// Loop-erased random walks, an unbiased maze
+    Wilson,
+    /// Recursive division, long straight walls
+    Division,
+    /// Row by row generation, with a slight horizontal bias
+    Eller,
+}
+
+pub static ALGORITHMS: &[Algorithm] = &[
+    Algorithm::GrowingTree,
+    Algorithm::Prim,
+    Algorithm::Kruskal,
+    Algorithm::Wilson,
+    Algorithm::Division,
+    Algorithm::Eller,
+];
+
+impl Algorithm {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::GrowingTree => "growing-tree",
+            Self::Prim => "prim",
+            Self::Kruskal => "kruskal",
+            Self::Wilson => "wilson",
+            Self::Division => "division",
+            Self::Eller => "eller",
+        }
+    }
+    pub fn generator(self) -> Box<dyn Generator> {
+        match self {
+            Self::GrowingTree => Box::<GrowingTree>::default(),
+            Self::Prim => Box::new(Prim),
+            Self::Kruskal => Box::new(Kruskal),
+            Self::Wilson => Box::new(Wilson),
+            Self::Division => Box::new(Division),
+            Self::Eller => Box::new(Eller),
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ALGORITHMS
+            .iter()
+            .find(|a| a.name() == s)
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = ALGORITHMS.iter().map(|a| a.name()).collect();
+                format!(
+                    "unknown algorithm {s:?}, expected one of {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+// The classic algorithms work on a grid whose cells are the positions
+// with odd coordinates, separated by walls at even coordinates.
+// The functions below help working on this grid.
+
+/// Return the position between two neighbour cells of the grid
+pub(crate) fn between(
+    a: Pos,
+    b: Pos,
+) -> Pos {
+    Pos::new((a.x + b.x) / 2, (a.y + b.y) / 2)
+}
+
+/// Return the neighbour cells of a grid cell, that is the ones 2 steps
+/// away, when both them and the wall between can be dug
+pub(crate) fn cell_neighbours(
+    maze: &Maze,
+    p: Pos,
+) -> SmallVec<[Pos; 4]> {
+    let mut list = SmallVec::new();
+    let mut candidates: SmallVec<[Pos; 4]> = SmallVec::new();
+    if p.y > 2 {
+        candidates.push(Pos::new(p.x, p.y - 2));
+    }
+    candidates.push(Pos::new(p.x + 2, p.y));
+    candidates.push(Pos::new(p.x, p.y + 2));
+    if p.x > 2 {
+        candidates.push(Pos::new(p.x - 2, p.y));
+    }
+    for c in candidates {
+        if maze.is_diggable(c) && maze.is_diggable(between(p, c)) {
+            list.push(c);
+        }
+    }
+    list
+}
+
+/// Return the root of the set of the cell whose index is given, in a
+/// union-find structure, compressing the path on the way
+pub(crate) fn find_root(
+    parents: &mut [usize],
+    mut idx: usize,
+) -> usize {
+    while parents[idx] != idx {
+        parents[idx] = parents[parents[idx]];
+        idx = parents[idx];
+    }
+    idx
+}
+
+/// Dig both cells and the wall between them
+pub(crate) fn connect(
+    maze: &mut Maze,
+    a: Pos,
+    b: Pos,
+) {
+    maze.dig(a);
+    maze.dig(between(a, b));
+    maze.dig(b);
+}
+
+/// Return the grid cells which can be connected to the start
+pub(crate) fn reachable_cells(maze: &Maze) -> Vec<Pos> {
+    let Some(start) = maze.start() else {
+        return Vec::new();
+    };
+    let mut seen = PosSet::new(maze.dim, false);
+    seen.set(start, true);
+    let mut cells = vec![start];
+    let mut i = 0;
+    while i < cells.len() {
+        for n in cell_neighbours(maze, cells[i]) {
+            if !seen.get(n) {
+                seen.set(n, true);
+                cells.push(n);
+            }
+        }
+        i += 1;
+    }
+    cells
+}
+
+/// The grid doesn't cover the last inside column when the width is even,
+/// nor the last inside row (the height is always even). This function
+/// opens a few cells there so that exits may be placed on all sides.
+pub(crate) fn fill_strips(maze: &mut Maze) {
+    let Dim { w, h } = maze.dim;
+    if w % 2 == 0 {
+        let x = w - 2;
+        for y in (1..h - 1).step_by(2) {
+            let p = Pos::new(x, y);
+            if maze.is_room(Pos::new(x - 1, y))
+                && maze.is_diggable(p)
+                && maze.rng().gen_range(0..3) == 0
+            {
+                maze.dig(p);
+            }
+        }
+    }
+    if h % 2 == 0 {
+        let y = h - 2;
+        for x in (1..w - 1).step_by(2) {
+            let p = Pos::new(x, y);
+            if maze.is_room(Pos::new(x, y - 1))
+                && maze.is_diggable(p)
+                && maze.rng().gen_range(0..3) == 0
+            {
+                maze.dig(p);
+            }
+        }
+    }
+}
+
+#[test]
+fn generators_carve_perfect_mazes() {
+    let shapes: Vec<Shape> = SHAPE_NAMES
+        .iter()
+        .map(|name| Shape::from_arg(name).unwrap())
+        .collect();
+    for &algorithm in ALGORITHMS {
+        for shape in &shapes {
+            for seed in 0..10 {
+                let mut specs = Specs::for_level(30);
+                specs.seed = Some(seed);
+                specs.algorithm = algorithm;
+                specs.set_shape(shape.clone());
+                // no loop, nor anything which could hide a room
+                specs.cuts = 0;
+                specs.doors = 0;
+                specs.monsters.clear();
+                let maze = Maze::from(specs);
+                let exit = maze.exit();
+                let mut rooms = 0;
+                let mut edges = 0;
+                for x in 0..maze.dim.w {
+                    for y in 0..maze.dim.h {
+                        let p = Pos::new(x, y);
+                        if !maze.is_room(p) {
+                            continue;
+                        }
+                        assert!(
+                            maze.is_diggable(p) || Some(p) == exit,
+                            "{algorithm} dug {p:?} out of the {shape:?} with seed {seed}"
+                        );
+                        rooms += 1;
+                        if x + 1 < maze.dim.w && maze.is_room(Pos::new(x + 1, y)) {
+                            edges += 1;
+                        }
+                        if y + 1 < maze.dim.h && maze.is_room(Pos::new(x, y + 1)) {
+                            edges += 1;
+                        }
+                    }
+                }
+                assert_eq!(
+                    rooms,
+                    edges + 1,
+                    "{algorithm} made loops in the {shape:?} with seed {seed}"
+                );
+                let from_start = path::DistanceMap::new(&maze, maze.start().unwrap());
+                let reached = (0..maze.dim.w)
+                    .flat_map(|x| (0..maze.dim.h).map(move |y| Pos::new(x, y)))
+                    .filter(|&p| from_start.distance(p).is_some())
+                    .count();
+                assert_eq!(
+                    reached, rooms,
+                    "{algorithm} left rooms unreachable in the {shape:?} with seed {seed}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use {
+    crate::*,
+    rand::Rng,
+};
+
+/// Recursive division: all cells are connected, then the area is
+/// divided by walls having only one passage, recursively.
+///
+/// It makes long straight walls.
+#[derive(Debug)]
+pub struct Division;
+
+/// A rectangle of grid cells, bounds included
+#[derive(Debug, Clone, Copy)]
+struct Chamber {
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+}
+
+impl Division {
+    /// Build a wall along the given positions, keeping one passage in
+    /// every run of open positions so that nothing gets isolated
+    fn build_wall(
+        maze: &mut Maze,
+        line: Vec<Pos>,
+    ) {
+        let mut run = Vec::new();
+        for p in line.into_iter().chain(std::iter::once(Pos::new(0, 0))) {
+            if maze.is_room(p) {
+                run.push(p);
+                continue;
+            }
+            if !run.is_empty() {
+                let gap = maze.rng().gen_range(0..run.len());
+                for (i, &q) in run.iter().enumerate() {
+                    if i != gap {
+                        maze.fill(q);
+                    }
+                }
+                run.clear();
+            }
+        }
+    }
+    /// Close the passages making loops, which the walls leave when the
+    /// shape has a hole, as both sides of the hole keep a passage.
+    ///
+    /// Passages are checked in order, without any random draw, so that
+    /// mazes without loop are unchanged.
+    fn remove_loops(
+        maze: &mut Maze,
+        cells: &[Pos],
+    ) {
+        let mut parents: Vec<usize> = (0..maze.dim.w * maze.dim.h).collect();
+        for &cell in cells {
+            for n in cell_neighbours(maze, cell) {
+                // each passage is only considered once, from its left or top cell
+                if !(n.x > cell.x || n.y > cell.y) || !maze.is_room(between(cell, n)) {
+                    continue;
+                }
+                let ra = find_root(&mut parents, maze.dim.idx(cell));
+                let rb = find_root(&mut parents, maze.dim.idx(n));
+                if ra == rb {
+                    maze.fill(between(cell, n));
+                } else {
+                    parents[rb] = ra;
+                }
+            }
+        }
+    }
+}
+
+impl Generator for Division {
+    fn carve(
+        &mut self,
+        maze: &mut Maze,
+        _fill: bool,
+    ) {
+        let cells = reachable_cells(maze);
+        if cells.is_empty() {
+            return;
+        }
+        let mut chamber = Chamber {
+            x0: cells[0].x,
+            y0: cells[0].y,
+            x1: cells[0].x,
+            y1: cells[0].y,
+        };
+        for &cell in &cells {
+            chamber.x0 = chamber.x0.min(cell.x);
+            chamber.y0 = chamber.y0.min(cell.y);
+            chamber.x1 = chamber.x1.max(cell.x);
+            chamber.y1 = chamber.y1.max(cell.y);
+            for n in cell_neighbours(maze, cell) {
+                connect(maze, cell, n);
+            }
+        }
+        let mut chambers = vec![chamber];
+        while let Some(Chamber { x0, y0, x1, y1 }) = chambers.pop() {
+            let (w, h) = (x1 - x0, y1 - y0);
+            if w == 0 && h == 0 {
+                continue;
+            }
+            let horizontal = if w == h {
+                maze.rng().gen_range(0..2) == 0
+            } else {
+                h > w
+            };
+            if horizontal {
+                // wall at y, between cell rows y-1 and y+1
+                let y = 2 * maze.rng().gen_range(0..h / 2) + y0 + 1;
+                let line = (x0..=x1).step_by(2).map(|x| Pos::new(x, y)).collect();
+                Self::build_wall(maze, line);
+                chambers.push(Chamber {
+                    x0,
+                    y0,
+                    x1,
+                    y1: y - 1,
+                });
+                chambers.push(Chamber {
+                    x0,
+                    y0: y + 1,
+                    x1,
+                    y1,
+                });
+            } else {
+                // wall at x, between cell columns x-1 and x+1
+                let x = 2 * maze.rng().gen_range(0..w / 2) + x0 + 1;
+                let line = (y0..=y1).step_by(2).map(|y| Pos::new(x, y)).collect();
+                Self::build_wall(maze, line);
+                chambers.push(Chamber {
+                    x0,
+                    y0,
+                    x1: x - 1,
+                    y1,
+                });
+                chambers.push(Chamber {
+                    x0: x + 1,
+                    y0,
+                    x1,
+                    y1,
+                });
+            }
+        }
+        Self::remove_loops(maze, &cells);
+        fill_strips(maze);
+    }
+}
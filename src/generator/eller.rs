@@ -0,0 +1,84 @@
+use {
+    crate::*,
+    rand::{
+        Rng,
+        seq::SliceRandom,
+    },
+};
+
+/// Eller's algorithm: the maze is built row by row, keeping track
+/// of which cells of the current row are already connected
+#[derive(Debug)]
+pub struct Eller;
+
+impl Generator for Eller {
+    fn carve(
+        &mut self,
+        maze: &mut Maze,
+        _fill: bool,
+    ) {
+        let Dim { w, h } = maze.dim;
+        // the set of every cell, 0 meaning no set yet
+        let mut sets: PosMap<usize> = PosMap::new(maze.dim, 0);
+        let mut next_set = 1;
+        let mut y = 1;
+        while y < h - 1 {
+            let row: Vec<Pos> = (1..w - 1)
+                .step_by(2)
+                .map(|x| Pos::new(x, y))
+                .filter(|&p| maze.is_diggable(p))
+                .collect();
+            let can_go_down = |maze: &Maze, p: Pos| {
+                maze.is_diggable(Pos::new(p.x, p.y + 1)) && maze.is_diggable(Pos::new(p.x, p.y + 2))
+            };
+            for &p in &row {
+                maze.dig(p);
+                if sets.get(p) == 0 {
+                    sets.set(p, next_set);
+                    next_set += 1;
+                }
+            }
+            // joining cells of the row which aren't in the same set.
+            // It's mandatory when one can't go down anymore.
+            for i in 1..row.len() {
+                let (a, b) = (row[i - 1], row[i]);
+                if b.x != a.x + 2 || !maze.is_diggable(between(a, b)) {
+                    continue;
+                }
+                let (sa, sb) = (sets.get(a), sets.get(b));
+                if sa == sb {
+                    continue;
+                }
+                let forced = !can_go_down(maze, a) || !can_go_down(maze, b);
+                if forced || maze.rng().gen_range(0..2) == 0 {
+                    connect(maze, a, b);
+                    for &p in &row {
+                        if sets.get(p) == sb {
+                            sets.set(p, sa);
+                        }
+                    }
+                }
+            }
+            // going down, at least once per set when possible
+            let mut downs: Vec<Pos> = row
+                .iter()
+                .copied()
+                .filter(|&p| can_go_down(maze, p))
+                .collect();
+            downs.shuffle(maze.rng());
+            let mut sets_down: Vec<usize> = Vec::new();
+            for p in downs {
+                let set = sets.get(p);
+                if sets_down.contains(&set) && maze.rng().gen_range(0..3) > 0 {
+                    continue;
+                }
+                let below = Pos::new(p.x, p.y + 2);
+                connect(maze, p, below);
+                sets.set(below, set);
+                sets_down.push(set);
+            }
+            y += 2;
+        }
+        fill_strips(maze);
+    }
+}
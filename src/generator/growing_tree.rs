@@ -0,0 +1,91 @@
+use {
+    crate::*,
+    rand::Rng,
+};
+
+/// The historical generator of mazter: a growing tree which, most of
+/// the time, extends one of the last openings, and sometimes an older one.
+#[derive(Debug, Default)]
+pub struct GrowingTree {
+    openings: Vec<Pos>, // where it's possible to dig a new cell
+}
+
+impl GrowingTree {
+    fn open(
+        &mut self,
+        maze: &mut Maze,
+        p: Pos,
+    ) {
+        maze.dig(p);
+        for n in maze.inside_neighbours(p) {
+            if maze.is_wall(n) && maze.is_diggable(n) {
+                self.openings.push(n);
+            }
+        }
+    }
+    fn seek_open(
+        &mut self,
+        maze: &mut Maze,
+    ) -> bool {
+        loop {
+            if self.openings.is_empty() {
+                return false;
+            }
+            let len = self.openings.len();
+            let tail = match len % 35 {
+                0 => len,
+                1 => len.min(15),
+                _ => len.min(4),
+            };
+            let idx: usize = maze.rng().gen_range(0..tail);
+            let opening = self.openings.swap_remove(len - idx - 1);
+            let neighbours = maze.inside_neighbours(opening);
+            let room_count = neighbours.iter().filter(|&p| maze.is_room(*p)).count();
+            if room_count != 1 {
+                continue;
+            }
+            self.open(maze, opening);
+            return true;
+        }
+    }
+    fn grow(
+        &mut self,
+        maze: &mut Maze,
+        max: usize,
+    ) -> usize {
+        for n in 0..max {
+            let open = self.seek_open(maze);
+            if !open {
+                return n;
+            }
+        }
+        max
+    }
+}
+
+impl Generator for GrowingTree {
+    fn carve(
+        &mut self,
+        maze: &mut Maze,
+        fill: bool,
+    ) {
+        let Some(start) = maze.start() else {
+            return;
+        };
+        self.open(maze, start);
+        if fill {
+            while self.grow(maze, 10) > 0 {}
+        } else {
+            let n = (maze.dim.w * maze.dim.h) / 3;
+            loop {
+                info!("growing 1");
+                if self.grow(maze, n) == 0 {
+                    break;
+                }
+                if maze.can_place_exit() {
+                    break;
+                }
+            }
+        }
+    }
+}
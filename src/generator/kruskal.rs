@@ -0,0 +1,40 @@
+use {
+    crate::*,
+    rand::seq::SliceRandom,
+};
+
+/// Randomized Kruskal: walls between cells are removed in random
+/// order, whenever they separate two not yet connected areas
+#[derive(Debug)]
+pub struct Kruskal;
+
+impl Generator for Kruskal {
+    fn carve(
+        &mut self,
+        maze: &mut Maze,
+        _fill: bool,
+    ) {
+        let cells = reachable_cells(maze);
+        let mut edges = Vec::new();
+        for &cell in &cells {
+            maze.dig(cell);
+            for n in cell_neighbours(maze, cell) {
+                // each edge is only considered once, from its left or top cell
+                if n.x > cell.x || n.y > cell.y {
+                    edges.push((cell, n));
+                }
+            }
+        }
+        edges.shuffle(maze.rng());
+        let mut parents: Vec<usize> = (0..maze.dim.w * maze.dim.h).collect();
+        for (a, b) in edges {
+            let ra = find_root(&mut parents, maze.dim.idx(a));
+            let rb = find_root(&mut parents, maze.dim.idx(b));
+            if ra != rb {
+                parents[rb] = ra;
+                connect(maze, a, b);
+            }
+        }
+        fill_strips(maze);
+    }
+}
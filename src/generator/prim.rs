@@ -0,0 +1,50 @@
+use {
+    crate::*,
+    rand::Rng,
+};
+
+/// Randomized Prim: the maze grows from a random cell of its frontier,
+/// which makes many short dead ends
+#[derive(Debug)]
+pub struct Prim;
+
+impl Generator for Prim {
+    fn carve(
+        &mut self,
+        maze: &mut Maze,
+        _fill: bool,
+    ) {
+        let Some(start) = maze.start() else {
+            return;
+        };
+        let mut in_maze = PosSet::new(maze.dim, false);
+        let mut in_frontier = PosSet::new(maze.dim, false);
+        let mut frontier = Vec::new();
+        maze.dig(start);
+        in_maze.set(start, true);
+        for n in cell_neighbours(maze, start) {
+            in_frontier.set(n, true);
+            frontier.push(n);
+        }
+        while !frontier.is_empty() {
+            let idx = maze.rng().gen_range(0..frontier.len());
+            let cell = frontier.swap_remove(idx);
+            let neighbours = cell_neighbours(maze, cell);
+            let inside: Vec<Pos> = neighbours
+                .iter()
+                .filter(|&&n| in_maze.get(n))
+                .copied()
+                .collect();
+            let idx = maze.rng().gen_range(0..inside.len());
+            connect(maze, cell, inside[idx]);
+            in_maze.set(cell, true);
+            for n in neighbours {
+                if !in_maze.get(n) && !in_frontier.get(n) {
+                    in_frontier.set(n, true);
+                    frontier.push(n);
+                }
+            }
+        }
+        fill_strips(maze);
+    }
+}
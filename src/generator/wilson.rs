@@ -0,0 +1,49 @@
+use {
+    crate::*,
+    rand::{
+        Rng,
+        seq::SliceRandom,
+    },
+};
+
+/// Wilson's algorithm: loop-erased random walks from every cell
+/// until they hit the maze, giving an unbiased maze
+#[derive(Debug)]
+pub struct Wilson;
+
+impl Generator for Wilson {
+    fn carve(
+        &mut self,
+        maze: &mut Maze,
+        _fill: bool,
+    ) {
+        let Some(start) = maze.start() else {
+            return;
+        };
+        let mut cells = reachable_cells(maze);
+        cells.shuffle(maze.rng());
+        let mut in_maze = PosSet::new(maze.dim, false);
+        maze.dig(start);
+        in_maze.set(start, true);
+        // for each cell of the walk, the direction it was last left by
+        let mut next: PosMap<Pos> = PosMap::new(maze.dim, start);
+        for cell in cells {
+            let mut current = cell;
+            while !in_maze.get(current) {
+                let neighbours = cell_neighbours(maze, current);
+                let idx = maze.rng().gen_range(0..neighbours.len());
+                next.set(current, neighbours[idx]);
+                current = neighbours[idx];
+            }
+            // following the last directions erases the loops
+            let mut current = cell;
+            while !in_maze.get(current) {
+                let n = next.get(current);
+                connect(maze, current, n);
+                in_maze.set(current, true);
+                current = n;
+            }
+        }
+        fill_strips(maze);
+    }
+}
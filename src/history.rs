@@ -0,0 +1,228 @@
+use crate::*;
+
+/// How many times the player may undo a turn or restart in a game
+pub const UNDOS_PER_GAME: usize = 3;
+
+/// The turns played since the start of a game, so that it can be rewound.
+///
+/// As turns only depend on the seed and on the player's actions, the
+/// game is rewound by playing them again on the maze as it was at start.
+pub struct History {
+    start: Maze,
+    actions: Vec<Action>,
+    undos: usize,
+    /// The hints used in the game, undone or restarted turns included
+    hints: usize,
+}
+
+impl History {
+    pub fn new(maze: &Maze) -> Self {
+        Self {
+            start: maze.clone(),
+            actions: Vec::new(),
+            undos: 0,
+            hints: 0,
+        }
+    }
+    /// Apply an action to the maze, keeping it in the history if it made
+    /// a turn, moved the player (bumping into a wall doesn't), gave a
+    /// hint, or ended the game
+    pub fn apply(
+        &mut self,
+        action: Action,
+        maze: &mut Maze,
+        events: &mut EventList,
+    ) {
+        let (turn, player, hints) = (maze.turn(), maze.player(), maze.hints_used());
+        action.apply(maze, events);
+        if maze.turn() != turn
+            || maze.player() != player
+            || maze.hints_used() != hints
+            || maze.is_lost()
+        {
+            self.actions.push(action);
+        }
+        self.hints = self.hints.max(maze.hints_used());
+    }
+    /// Rebuild the history of a game from its actions, and return it with
+    /// the maze as it was after them
+    pub fn restore(
+        start: Maze,
+        actions: Vec<Action>,
+        undos: usize,
+        hints: usize,
+    ) -> (Self, Maze) {
+        let history = Self {
+            start,
+            actions,
+            undos: undos.min(UNDOS_PER_GAME),
+            hints,
+        };
+        let maze = history.replayed();
+        (history, maze)
+    }
+    /// The actions of the turns played, undone ones excluded
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+    pub fn undos_used(&self) -> usize {
+        self.undos
+    }
+    pub fn undos_left(&self) -> usize {
+        UNDOS_PER_GAME - self.undos
+    }
+    pub fn hints_used(&self) -> usize {
+        self.hints
+    }
+    /// Return the maze as it was at the start of the previous turn, if
+    /// there's one and the player didn't use all their undos
+    ///
+    /// The actions which didn't make a turn (hints, or moves of a timed
+    /// game) are undone with the turn they follow.
+    pub fn undo(&mut self) -> Option<Maze> {
+        if self.undos >= UNDOS_PER_GAME || self.actions.is_empty() {
+            return None;
+        }
+        let mut maze = self.start.clone();
+        let mut events = EventList::default();
+        let mut turns = Vec::with_capacity(self.actions.len());
+        for action in &self.actions {
+            turns.push(maze.turn());
+            action.apply(&mut maze, &mut events);
+            events.clear();
+        }
+        let previous_turn = maze.turn().saturating_sub(1);
+        let kept = turns
+            .iter()
+            .take_while(|&&turn| turn < previous_turn)
+            .count();
+        self.actions.truncate(kept);
+        self.undos += 1;
+        Some(self.replayed())
+    }
+    /// Return the maze as it was at start, if the player didn't use all
+    /// their undos, restarting using one
+    pub fn restart(&mut self) -> Option<Maze> {
+        if self.undos >= UNDOS_PER_GAME || self.actions.is_empty() {
+            return None;
+        }
+        self.actions.clear();
+        self.undos += 1;
+        Some(self.replayed())
+    }
+    fn replayed(&self) -> Maze {
+        let mut maze = self.start.clone();
+        let mut events = EventList::default();
+        for action in &self.actions {
+            action.apply(&mut maze, &mut events);
+            events.clear();
+        }
+        maze.set_hints_used(maze.hints_used().max(self.hints));
+        maze
+    }
+}
+
+#[test]
+fn undo_rewinds_one_turn() {
+    let mut specs = Specs::for_level(27);
+    specs.seed = Some(1234);
+    let mut maze = Maze::from(specs);
+    let mut history = History::new(&maze);
+    let mut events = EventList::default();
+    let mut before_last = maze.clone();
+    for _ in 0..20 {
+        before_last = maze.clone();
+        history.apply(Action::Auto, &mut maze, &mut events);
+        events.clear();
+    }
+    let undone = history.undo().unwrap();
+    assert_eq!(undone.turn(), before_last.turn());
+    assert_eq!(undone.player(), before_last.player());
+    assert_eq!(MazeData::from(&undone), MazeData::from(&before_last));
+    assert_eq!(history.undos_left(), UNDOS_PER_GAME - 1);
+    let (_, restored) = History::restore(
+        history.start.clone(),
+        history.actions().to_vec(),
+        history.undos_used(),
+        history.hints_used(),
+    );
+    assert_eq!(restored.turn(), undone.turn());
+    assert_eq!(history.restart().unwrap().turn(), 0);
+    assert_eq!(history.undos_left(), UNDOS_PER_GAME - 2);
+}
+
+#[test]
+fn undo_revives_the_player() {
+    let mut specs = Specs::for_level(12);
+    specs.seed = Some(42);
+    let maze = Maze::from(specs);
+    let start = maze.start().unwrap();
+    let first_step = maze.player_path_to(maze.exit().unwrap()).unwrap()[0];
+    // a monster waits on the way
+    let mut data = MazeData::from(&maze);
+    data.monsters = vec![Monster::new(MonsterKind::Slow, first_step)];
+    let mut maze = Maze::try_from(data).unwrap();
+    maze.lives = 1;
+    let mut history = History::new(&maze);
+    let mut events = EventList::default();
+    history.apply(
+        Action::Move(start.dir_to(first_step)),
+        &mut maze,
+        &mut events,
+    );
+    assert!(maze.is_lost());
+    let undone = history.undo().unwrap();
+    assert!(!undone.is_lost());
+    assert_eq!(undone.player(), Some(start));
+}
+
+#[test]
+fn hints_stay_used_after_a_restart() {
+    let mut specs = Specs::for_level(12);
+    specs.seed = Some(42);
+    let mut maze = Maze::from(specs);
+    let mut history = History::new(&maze);
+    let mut events = EventList::default();
+    history.apply(Action::Hint, &mut maze, &mut events);
+    assert_eq!(maze.hints_used(), 1);
+    let mut maze = history.restart().unwrap();
+    assert_eq!(maze.hints_used(), 1);
+    while !maze.is_won() {
+        assert!(!maze.is_lost());
+        history.apply(Action::Auto, &mut maze, &mut events);
+        events.clear();
+    }
+    let score = Score::new(&maze, std::time::Duration::from_secs(10));
+    assert_eq!(score.hints, 1);
+}
+
+#[test]
+fn undo_rewinds_whole_turns() {
+    let mut specs = Specs::for_level(12);
+    specs.seed = Some(42);
+    let mut maze = Maze::from(specs);
+    let clock = Clock::new(&maze, 500);
+    maze.set_clock(Some(clock));
+    let mut history = History::new(&maze);
+    let mut events = EventList::default();
+    let path = maze.player_path_to(maze.exit().unwrap()).unwrap();
+    let mut player = maze.start().unwrap();
+    // in a timed game, several moves and a hint may be done in a turn
+    for &step in &path[..2] {
+        history.apply(Action::Move(player.dir_to(step)), &mut maze, &mut events);
+        player = step;
+    }
+    history.apply(Action::Tick, &mut maze, &mut events);
+    let after_first_turn = maze.clone();
+    for &step in &path[2..4] {
+        history.apply(Action::Move(player.dir_to(step)), &mut maze, &mut events);
+        player = step;
+    }
+    history.apply(Action::Tick, &mut maze, &mut events);
+    history.apply(Action::Hint, &mut maze, &mut events);
+    assert_eq!(maze.turn(), 2);
+    let undone = history.undo().unwrap();
+    assert_eq!(undone.turn(), 1);
+    assert_eq!(undone.player(), after_first_turn.player());
+    assert_eq!(history.undos_left(), UNDOS_PER_GAME - 1);
+}
@@ -1,5 +1,7 @@
 use {
     crate::*,
+    chrono::NaiveDate,
+    std::str::FromStr,
     termimad::{
         MadSkin,
         minimad::{
@@ -32,3 +34,120 @@ pub fn print() -> anyhow::Result<()> {
     skin.print_owning_expander_md(&expander, &tbl);
     Ok(())
 }
+
+/// How players are ranked on a level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Ranking {
+    /// Fewest moves not on the shortest way first
+    #[default]
+    Moves,
+    /// Fastest first
+    Time,
+}
+
+impl FromStr for Ranking {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "moves" => Ok(Self::Moves),
+            "time" => Ok(Self::Time),
+            _ => Err(format!("unknown ranking {s:?}, expected moves or time")),
+        }
+    }
+}
+
+// display the best score of every player on a level, among the games
+// with the given clock period, or untimed
+pub fn print_level(
+    level: usize,
+    ranking: Ranking,
+    period: Option<u64>,
+) -> anyhow::Result<()> {
+    let mut scores = Database::level_scores(level)?;
+    scores.retain(|(_, score)| score.period == period);
+    if scores.is_empty() {
+        println!("Nobody won level {level}{} yet", timing(period));
+        return Ok(());
+    }
+    print_scores(scores, ranking);
+    Ok(())
+}
+
+// display the best score of every player on the daily challenge of a date,
+// among the games with the given clock period, or untimed
+pub fn print_daily(
+    date: NaiveDate,
+    ranking: Ranking,
+    period: Option<u64>,
+) -> anyhow::Result<()> {
+    let mut scores = Database::daily_scores(date)?;
+    scores.retain(|(_, score)| score.period == period);
+    if scores.is_empty() {
+        println!(
+            "Nobody won the daily challenge of {date}{} yet",
+            timing(period)
+        );
+        return Ok(());
+    }
+    print_scores(scores, ranking);
+    Ok(())
+}
+
+fn timing(period: Option<u64>) -> String {
+    match period {
+        Some(period) => format!(" in timed games of {period}ms"),
+        None => String::new(),
+    }
+}
+
+fn print_scores(
+    scores: Vec<(String, Score)>,
+    ranking: Ranking,
+) {
+    // wins with fewer hints come first
+    let key = |score: &Score| match ranking {
+        Ranking::Moves => (
+            score.hints,
+            score.extra_turns() as u128,
+            score.duration.as_millis(),
+        ),
+        Ranking::Time => (
+            score.hints,
+            score.duration.as_millis(),
+            score.extra_turns() as u128,
+        ),
+    };
+    let mut best: Vec<(String, Score)> = Vec::new();
+    for (user, score) in scores {
+        if let Some(idx) = best.iter().position(|(u, _)| *u == user) {
+            if key(&score) < key(&best[idx].1) {
+                best[idx].1 = score;
+            }
+        } else {
+            best.push((user, score));
+        }
+    }
+    best.sort_by_key(|(_, score)| key(score));
+    let mut expander = OwningTemplateExpander::new();
+    for (user, score) in &best {
+        expander
+            .sub("rows")
+            .set("user", user)
+            .set("moves", score.turns)
+            .set("optimal", score.optimal)
+            .set("time", format!("{:.1}s", score.duration.as_secs_f64()))
+            .set("lives", score.lives)
+            .set("potions", score.potions)
+            .set("hints", score.hints);
+    }
+    let mut tbl = TableBuilder::default();
+    tbl.col(Col::new("**User**", "${user}"));
+    tbl.col(Col::new("**Moves**", "${moves}").align_content(Alignment::Right));
+    tbl.col(Col::new("**Optimal**", "${optimal}").align_content(Alignment::Right));
+    tbl.col(Col::new("**Time**", "${time}").align_content(Alignment::Right));
+    tbl.col(Col::new("**Lives**", "${lives}").align_content(Alignment::Right));
+    tbl.col(Col::new("**Potions**", "${potions}").align_content(Alignment::Right));
+    tbl.col(Col::new("**Hints**", "${hints}").align_content(Alignment::Right));
+    let skin = MadSkin::default();
+    skin.print_owning_expander_md(&expander, &tbl);
+}
@@ -0,0 +1,322 @@
+use {
+    crate::*,
+    anyhow::{
+        anyhow,
+        bail,
+    },
+    crokey::{
+        KeyCombination,
+        OneToThree,
+        crossterm::event::{
+            KeyCode,
+            KeyModifiers,
+        },
+        key,
+    },
+    std::{
+        borrow::Cow,
+        fs,
+    },
+};
+
+/// What the player may ask with a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    MoveUp,
+    MoveRight,
+    MoveDown,
+    MoveLeft,
+    Wait,
+    GiveUp,
+    /// Show the next rooms of the way
+    Hint,
+    /// Show or hide the rooms the player went through
+    Trail,
+    /// Go back one turn
+    Undo,
+    /// Play the same maze again from the start
+    Restart,
+    /// Quit, keeping the game to resume it at next launch
+    SaveAndQuit,
+    Quit,
+    /// Show the active key bindings
+    Help,
+}
+
+pub static COMMANDS: &[Command] = &[
+    Command::MoveUp,
+    Command::MoveRight,
+    Command::MoveDown,
+    Command::MoveLeft,
+    Command::Wait,
+    Command::GiveUp,
+    Command::Hint,
+    Command::Trail,
+    Command::Undo,
+    Command::Restart,
+    Command::SaveAndQuit,
+    Command::Quit,
+    Command::Help,
+];
+
+/// The keymaps shipped with mazter, with their names
+pub static KEYMAP_PRESETS: &[(&str, &str)] = &[
+    ("arrows", include_str!("../resources/keymaps/arrows.toml")),
+    ("vim", include_str!("../resources/keymaps/vim.toml")),
+    ("wasd", include_str!("../resources/keymaps/wasd.toml")),
+];
+
+impl Command {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::MoveUp => "move-up",
+            Self::MoveRight => "move-right",
+            Self::MoveDown => "move-down",
+            Self::MoveLeft => "move-left",
+            Self::Wait => "wait",
+            Self::GiveUp => "give-up",
+            Self::Hint => "hint",
+            Self::Trail => "trail",
+            Self::Undo => "undo",
+            Self::Restart => "restart",
+            Self::SaveAndQuit => "save-and-quit",
+            Self::Quit => "quit",
+            Self::Help => "help",
+        }
+    }
+    pub fn description(self) -> Cow<'static, str> {
+        match self {
+            Self::MoveUp => "move up".into(),
+            Self::MoveRight => "move right".into(),
+            Self::MoveDown => "move down".into(),
+            Self::MoveLeft => "move left".into(),
+            Self::Wait => "let the monsters move without moving".into(),
+            Self::GiveUp => "abandon the level".into(),
+            Self::Hint => {
+                format!("show the next rooms of the way ({HINTS_PER_LEVEL} times per level)").into()
+            }
+            Self::Trail => "show or hide the rooms you went through".into(),
+            Self::Undo => format!("go back one turn ({UNDOS_PER_GAME} times per game)").into(),
+            Self::Restart => "play the same maze again from the start (uses one undo)".into(),
+            Self::SaveAndQuit => "quit, and resume the game at next launch".into(),
+            Self::Quit => "quit mazter".into(),
+            Self::Help => "show this help".into(),
+        }
+    }
+    /// Return the game action of the command, if it's one
+    pub fn action(self) -> Option<Action> {
+        match self {
+            Self::MoveUp => Some(Action::Move(Dir::Up)),
+            Self::MoveRight => Some(Action::Move(Dir::Right)),
+            Self::MoveDown => Some(Action::Move(Dir::Down)),
+            Self::MoveLeft => Some(Action::Move(Dir::Left)),
+            Self::Wait => Some(Action::Wait),
+            Self::GiveUp => Some(Action::GiveUp),
+            Self::Hint => Some(Action::Hint),
+            Self::Trail
+            | Self::Undo
+            | Self::Restart
+            | Self::SaveAndQuit
+            | Self::Quit
+            | Self::Help => None,
+        }
+    }
+}
+
+/// The bindings of keys to commands
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    bindings: Vec<(KeyCombination, Command)>,
+}
+
+impl Keymap {
+    /// Build a keymap from a TOML file of `key = "command"` lines, where
+    /// keys are written as in `ctrl-q` or `"?"`.
+    ///
+    /// A `preset = "vim"` line imports the bindings of a preset, the
+    /// following lines adding to them or replacing them.
+    pub fn from_toml(toml: &str) -> anyhow::Result<Self> {
+        let mut keymap = Self::default();
+        for entry in parse_flat_toml(toml).map_err(|e| anyhow!("Invalid keymap: {e}"))? {
+            if entry.name == "preset" {
+                let preset = Self::preset(&entry.value)?;
+                for (key, command) in preset.bindings {
+                    keymap.bind(key, command);
+                }
+                continue;
+            }
+            let key: KeyCombination = entry
+                .name
+                .parse()
+                .map_err(|_| anyhow!("Invalid key {:?}", entry.name))?;
+            let Some(&command) = COMMANDS.iter().find(|c| c.name() == entry.value) else {
+                let names: Vec<&str> = COMMANDS.iter().map(|c| c.name()).collect();
+                bail!(
+                    "Unknown command {:?} for key {:?}, expected one of {}",
+                    entry.value,
+                    entry.name,
+                    names.join(", ")
+                );
+            };
+            keymap.bind(key, command);
+        }
+        Ok(keymap)
+    }
+    pub fn preset(name: &str) -> anyhow::Result<Self> {
+        match KEYMAP_PRESETS.iter().find(|(n, _)| *n == name) {
+            Some((_, content)) => Self::from_toml(content),
+            None => {
+                let names: Vec<&str> = KEYMAP_PRESETS.iter().map(|(name, _)| *name).collect();
+                bail!(
+                    "Unknown keymap preset {name:?}, expected one of {}",
+                    names.join(", ")
+                );
+            }
+        }
+    }
+    /// Bind a key to a command, removing its previous binding
+    pub fn bind(
+        &mut self,
+        key: KeyCombination,
+        command: Command,
+    ) {
+        self.bindings.retain(|(k, _)| *k != key);
+        self.bindings.push((key, command));
+    }
+    /// Return the command bound to a key, if any
+    pub fn command(
+        &self,
+        key: KeyCombination,
+    ) -> Option<Command> {
+        let find = |key: KeyCombination| {
+            self.bindings
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, command)| *command)
+        };
+        find(key).or_else(|| {
+            // terminals don't agree on whether symbols like '?' come with shift
+            match key {
+                KeyCombination {
+                    codes: OneToThree::One(KeyCode::Char(c)),
+                    modifiers: KeyModifiers::SHIFT,
+                } if !c.is_alphabetic() => find(KeyCombination::one_key(
+                    KeyCode::Char(c),
+                    KeyModifiers::NONE,
+                )),
+                _ => None,
+            }
+        })
+    }
+    /// Adapt a tutorial message, written for the default keys, to this
+    /// keymap
+    pub fn tutorial<'m>(
+        &self,
+        message: &'m str,
+    ) -> Cow<'m, str> {
+        let key = |command| {
+            self.keys(command)
+                .first()
+                .map_or_else(|| "?".to_string(), |key| key.to_string())
+        };
+        match message {
+            MOVE_TUTORIAL => {
+                let arrows = [
+                    (key!(up), Command::MoveUp),
+                    (key!(right), Command::MoveRight),
+                    (key!(down), Command::MoveDown),
+                    (key!(left), Command::MoveLeft),
+                ];
+                if arrows.iter().all(|&(k, c)| self.command(k) == Some(c)) {
+                    return message.into();
+                }
+                let keys = [
+                    key(Command::MoveUp),
+                    key(Command::MoveLeft),
+                    key(Command::MoveDown),
+                    key(Command::MoveRight),
+                ];
+                format!("Use {} to move and exit the maze", keys.join(" ")).into()
+            }
+            GIVE_UP_TUTORIAL => {
+                format!("You can abandon with key '{}'", key(Command::GiveUp)).into()
+            }
+            WAIT_TUTORIAL => format!("Hit '{}' to wait", key(Command::Wait)).into(),
+            _ => message.into(),
+        }
+    }
+    /// Return the keys bound to a command
+    pub fn keys(
+        &self,
+        command: Command,
+    ) -> Vec<KeyCombination> {
+        self.bindings
+            .iter()
+            .filter(|(_, c)| *c == command)
+            .map(|(key, _)| *key)
+            .collect()
+    }
+}
+
+/// Load the keymap given as argument, as the name of a preset or as the
+/// path to a file.
+///
+/// Without any, the `keys.toml` file of the config directory is used if it
+/// exists, and the `arrows` preset if it doesn't.
+pub fn load_keymap(keys: Option<&str>) -> anyhow::Result<Keymap> {
+    if let Some(keys) = keys {
+        if KEYMAP_PRESETS.iter().any(|(name, _)| *name == keys) {
+            return Keymap::preset(keys);
+        }
+        let content = fs::read_to_string(keys).map_err(|e| {
+            let names: Vec<&str> = KEYMAP_PRESETS.iter().map(|(name, _)| *name).collect();
+            anyhow!(
+                "{keys:?} is neither a keymap preset ({}) nor a readable file: {e}",
+                names.join(", ")
+            )
+        })?;
+        return Keymap::from_toml(&content);
+    }
+    let path = config_dir()?.join("keys.toml");
+    if !path.exists() {
+        return Keymap::preset("arrows");
+    }
+    debug!("keymap: {path:?}");
+    Keymap::from_toml(&fs::read_to_string(path)?)
+}
+
+#[test]
+fn keymap_presets_are_valid() {
+    for (name, _) in KEYMAP_PRESETS {
+        let keymap = Keymap::preset(name).unwrap();
+        for &command in COMMANDS {
+            assert!(
+                !keymap.keys(command).is_empty(),
+                "no key for {} in {name}",
+                command.name()
+            );
+        }
+    }
+    let keymap = Keymap::from_toml("preset = \"vim\"\nk = \"wait\" # not up").unwrap();
+    assert_eq!(keymap.command(key!(k)), Some(Command::Wait));
+    assert_eq!(keymap.command(key!(j)), Some(Command::MoveDown));
+    assert_eq!(keymap.command(key!(shift - '?')), Some(Command::Help));
+    assert!(Keymap::from_toml("k = \"jump\"").is_err());
+    assert_eq!(parse_flat_toml(r#""\"" = "help""#).unwrap()[0].name, "\"");
+    let arrows = Keymap::preset("arrows").unwrap();
+    assert_eq!(arrows.tutorial(WAIT_TUTORIAL), WAIT_TUTORIAL);
+    assert_eq!(arrows.tutorial(GIVE_UP_TUTORIAL), GIVE_UP_TUTORIAL);
+    let wasd = Keymap::preset("wasd").unwrap();
+    assert_eq!(
+        wasd.tutorial(GIVE_UP_TUTORIAL),
+        "You can abandon with key 'g'"
+    );
+    let hjkl = Keymap::from_toml(
+        "k = \"move-up\"\nh = \"move-left\"\nj = \"move-down\"\nl = \"move-right\"",
+    )
+    .unwrap();
+    assert_eq!(
+        hjkl.tutorial(MOVE_TUTORIAL),
+        "Use k h j l to move and exit the maze"
+    );
+}
@@ -1,27 +1,40 @@
 use crate::*;
 
+/// How many terminal cells a maze position takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Density {
+    /// Two cells side by side per position
+    Double,
+    /// One cell per position
+    Single,
+    /// Two positions, one above the other, per cell
+    Half,
+    /// Eight positions, two columns of four, per braille character
+    Braille,
+}
+
 #[derive(Debug, Clone)]
 pub struct Layout {
     pub content: Dim,
     pub margin: Dim, // top and left margin for the whole (including texts)
     pub trim: Dim,   // what part of the maze, left and top, is out of screen
-    pub double_sizes: bool,
+    pub density: Density,
 }
 
 impl Layout {
     /// return the canonical cell position from a maze position.
     ///
-    /// If the layout is double_sizes, this is the leftest one of the two
+    /// If the layout is double density, this is the leftest one of the two
     ///   cells which make the position.
-    /// If the layout is half size, only one half of the cell belongs to
+    /// If the layout is half density, only one half of the cell belongs to
     ///   the provided maze position.
     /// Return None if the position is out of the maze rendering area (can't
-    ///   happen in double_sizes mode)
+    ///   happen in double density)
     pub fn maze_to_screen(
         &self,
         pos: Pos,
     ) -> Option<(u16, u16)> {
-        if self.double_sizes {
+        if self.density == Density::Double {
             Some(self.maze_to_screen_double_size(pos))
         } else {
             if pos.x < self.trim.w || pos.y < self.trim.h {
@@ -29,17 +42,55 @@ impl Layout {
             } else {
                 let x = pos.x - self.trim.w;
                 let y = pos.y - self.trim.h;
+                let (x, y) = match self.density {
+                    Density::Half => (x, y / 2),
+                    Density::Braille => (x / 2, y / 4),
+                    _ => (x, y),
+                };
                 if x >= self.content.w || y >= self.content.h {
                     None
                 } else {
                     let x = self.margin.w + x;
-                    let y = self.margin.h + 1 + y / 2; // 1 for the top texts
+                    let y = self.margin.h + 1 + y; // 1 for the top texts
                     Some((x as u16, y as u16))
                 }
             }
         }
     }
-    /// Assuming the layout is in double_sizes mode, return the leftest
+    /// Return the maze positions drawn in a screen cell: one position in
+    /// double and single densities, a column of two in half density, a
+    /// block of 2x4 in braille density.
+    ///
+    /// Return an empty list when the cell is out of the maze rendering area
+    pub fn screen_to_maze(
+        &self,
+        x: u16,
+        y: u16,
+    ) -> Vec<Pos> {
+        let (x, y) = (x as usize, y as usize);
+        let top = self.margin.h + 1; // 1 for the top texts
+        if x < self.margin.w || y < top {
+            return Vec::new();
+        }
+        let (x, y) = (x - self.margin.w, y - top);
+        if x >= self.content.w || y >= self.content.h {
+            return Vec::new();
+        }
+        let (x, y, per_cell) = match self.density {
+            Density::Double => (x / 2, y, Dim::new(1, 1)),
+            Density::Single => (x, y, Dim::new(1, 1)),
+            Density::Half => (x, 2 * y, Dim::new(1, 2)),
+            Density::Braille => (2 * x, 4 * y, Dim::new(2, 4)),
+        };
+        let mut positions = Vec::new();
+        for dy in 0..per_cell.h {
+            for dx in 0..per_cell.w {
+                positions.push(Pos::new(self.trim.w + x + dx, self.trim.h + y + dy));
+            }
+        }
+        positions
+    }
+    /// Assuming the layout is in double density, return the leftest
     /// cell for tye maze position
     pub fn maze_to_screen_double_size(
         &self,
@@ -49,10 +100,15 @@ impl Layout {
         let y = self.margin.h + pos.y + 1; // 1 for the top texts
         (x as u16, y as u16)
     }
+    /// The number of braille characters needed to draw the whole maze
+    fn braille_dim(maze_dim: Dim) -> Dim {
+        Dim::new((maze_dim.w + 1) / 2, (maze_dim.h + 3) / 4)
+    }
     pub fn compute(
         maze_dim: Dim,
         player_pos: Option<Pos>,
         display: Display,
+        charset: Charset,
     ) -> Self {
         let content_width;
         let content_height;
@@ -60,16 +116,56 @@ impl Layout {
         let mut top_trim = 0;
         let left_margin;
         let top_margin;
-        let double_sizes;
+        let density;
         // we assume maze_dim.h is fair (it must be)
         match display {
             Display::Alternate(Dim { w, h }) => {
                 let available_width = w;
                 let available_height = h - 3;
-                double_sizes = 2 * maze_dim.w < available_width && maze_dim.h < available_height;
-                if double_sizes {
+                density = if charset.is_text() {
+                    Density::Single
+                } else if charset == Charset::Braille {
+                    Density::Braille
+                } else if 2 * maze_dim.w < available_width && maze_dim.h < available_height {
+                    Density::Double
+                } else if maze_dim.w <= available_width && maze_dim.h / 2 <= available_height {
+                    Density::Half
+                } else {
+                    // the half size layout would have to trim the maze
+                    Density::Braille
+                };
+                if density == Density::Double {
                     content_width = 2 * maze_dim.w;
                     content_height = maze_dim.h;
+                } else if density == Density::Single {
+                    content_width = maze_dim.w.min(available_width);
+                    content_height = maze_dim.h.min(available_height);
+                    // the player is kept near the center when the maze doesn't fit
+                    if let Some(player) = player_pos {
+                        left_trim = player
+                            .x
+                            .saturating_sub(available_width / 2)
+                            .min(maze_dim.w - content_width);
+                        top_trim = player
+                            .y
+                            .saturating_sub(available_height / 2)
+                            .min(maze_dim.h - content_height);
+                    }
+                } else if density == Density::Braille {
+                    let braille_dim = Self::braille_dim(maze_dim);
+                    content_width = braille_dim.w.min(available_width);
+                    content_height = braille_dim.h.min(available_height);
+                    // trims are in maze positions, but must fall on braille characters
+                    if let Some(player) = player_pos {
+                        left_trim = 2
+                            * (player.x / 2)
+                                .saturating_sub(available_width / 2)
+                                .min(braille_dim.w - content_width);
+                        top_trim = 4
+                            * (player.y / 4)
+                                .saturating_sub(available_height / 2)
+                                .min(braille_dim.h - content_height);
+                    }
                 } else {
                     if maze_dim.w > available_width {
                         content_width = available_width;
@@ -100,21 +196,64 @@ impl Layout {
             Display::Standard => {
                 left_margin = 1;
                 top_margin = 0;
-                double_sizes = maze_dim.w < 20 && maze_dim.h < 30;
-                if double_sizes {
-                    content_width = 2 * maze_dim.w;
-                    content_height = maze_dim.h;
+                density = if charset.is_text() {
+                    Density::Single
+                } else if charset == Charset::Braille {
+                    Density::Braille
+                } else if maze_dim.w < 20 && maze_dim.h < 30 {
+                    Density::Double
                 } else {
-                    content_width = maze_dim.w;
-                    content_height = maze_dim.h / 2;
+                    Density::Half
                 };
+                match density {
+                    Density::Double => {
+                        content_width = 2 * maze_dim.w;
+                        content_height = maze_dim.h;
+                    }
+                    Density::Single => {
+                        content_width = maze_dim.w;
+                        content_height = maze_dim.h;
+                    }
+                    Density::Half => {
+                        content_width = maze_dim.w;
+                        content_height = maze_dim.h / 2;
+                    }
+                    Density::Braille => {
+                        let braille_dim = Self::braille_dim(maze_dim);
+                        content_width = braille_dim.w;
+                        content_height = braille_dim.h;
+                    }
+                }
             }
         }
         Self {
             content: Dim::new(content_width, content_height),
             margin: Dim::new(left_margin, top_margin),
             trim: Dim::new(left_trim, top_trim),
-            double_sizes,
+            density,
+        }
+    }
+}
+
+#[test]
+fn huge_mazes_are_shown_whole_in_braille() {
+    let display = Display::Alternate(Dim::new(80, 24));
+    let layout = Layout::compute(Dim::new(60, 40), None, display, Charset::Blocks);
+    assert_eq!(layout.density, Density::Half);
+    let layout = Layout::compute(Dim::new(150, 80), None, display, Charset::Blocks);
+    assert_eq!(layout.density, Density::Braille);
+    assert_eq!(layout.content, Dim::new(75, 20));
+    assert_eq!(layout.trim, Dim::new(0, 0));
+}
+
+#[test]
+fn screen_to_maze_is_the_inverse_of_maze_to_screen() {
+    let display = Display::Alternate(Dim::new(80, 24));
+    for maze_dim in [Dim::new(20, 14), Dim::new(60, 40), Dim::new(150, 80)] {
+        let layout = Layout::compute(maze_dim, None, display, Charset::Blocks);
+        for pos in [Pos::new(3, 5), Pos::new(maze_dim.w - 1, maze_dim.h - 1)] {
+            let (x, y) = layout.maze_to_screen(pos).unwrap();
+            assert!(layout.screen_to_maze(x, y).contains(&pos));
         }
     }
 }
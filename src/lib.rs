@@ -0,0 +1,111 @@
+//! Mazter builds mazes and lets you play them.
+//!
+//! The core of this crate, which doesn't depend on any terminal, covers
+//! - the generation of mazes: [Specs], [Shape], [Algorithm] and the [Generator]s
+//! - the rules of the game: [Maze], [Action]
+//! - the solving of mazes: [path::find_astar], [path::DistanceMap]
+//! - the import and export of mazes: [MazeData]
+//!
+//! ```
+//! use mazter::*;
+//!
+//! let mut specs = Specs::for_level(12);
+//! specs.seed = Some(42);
+//! let maze = Maze::from(specs);
+//! let path = path::find_astar(&maze, maze.start().unwrap(), maze.exit().unwrap());
+//! assert!(path.is_some());
+//! ```
+//!
+//! The terminal game itself is behind the `tui` feature, enabled by default.
+
+#[macro_use]
+extern crate cli_log;
+
+mod achievements;
+mod action;
+mod clock;
+mod dim;
+mod events;
+mod format;
+mod generator;
+mod history;
+mod lock;
+mod maze;
+mod maze_data;
+mod maze_text;
+mod monster;
+mod nature;
+pub mod path;
+mod pos;
+mod pos_map;
+mod replay;
+mod saved_game;
+mod shape;
+mod simulate;
+mod specs;
+
+#[cfg(feature = "tui")]
+mod args;
+#[cfg(feature = "tui")]
+mod cell_draw;
+#[cfg(feature = "tui")]
+mod charset;
+#[cfg(feature = "tui")]
+mod display;
+#[cfg(feature = "tui")]
+mod flat_toml;
+#[cfg(feature = "tui")]
+pub mod hof;
+#[cfg(feature = "tui")]
+mod keymap;
+#[cfg(feature = "tui")]
+mod layout;
+#[cfg(feature = "tui")]
+mod renderer;
+#[cfg(feature = "tui")]
+mod run;
+#[cfg(feature = "tui")]
+mod skin;
+#[cfg(feature = "tui")]
+mod svg;
+#[cfg(feature = "tui")]
+mod theme;
+
+pub use {
+    achievements::*,
+    action::*,
+    clock::*,
+    dim::*,
+    events::*,
+    format::*,
+    generator::*,
+    history::*,
+    lock::*,
+    maze::*,
+    maze_data::*,
+    monster::*,
+    nature::*,
+    pos::*,
+    pos_map::*,
+    replay::*,
+    saved_game::*,
+    shape::*,
+    simulate::*,
+    specs::*,
+};
+
+#[cfg(feature = "tui")]
+pub use {
+    args::*,
+    cell_draw::*,
+    charset::*,
+    display::*,
+    flat_toml::*,
+    keymap::*,
+    layout::*,
+    renderer::*,
+    run::*,
+    skin::*,
+    svg::*,
+    theme::*,
+};
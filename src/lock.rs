@@ -0,0 +1,33 @@
+use crate::*;
+
+/// A locked door, and the key the player must pick to open it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Lock {
+    pub door: Pos,
+    pub key: Pos,
+    /// Whether the player picked the key
+    #[serde(default)]
+    pub key_taken: bool,
+    /// Whether the player went through the door, using the key
+    #[serde(default)]
+    pub open: bool,
+}
+
+impl Lock {
+    pub fn new(
+        door: Pos,
+        key: Pos,
+    ) -> Self {
+        Self {
+            door,
+            key,
+            key_taken: false,
+            open: false,
+        }
+    }
+    /// Tell whether the door blocks the way: once the key is picked,
+    /// it's no longer locked, even if still closed
+    pub fn is_locked(&self) -> bool {
+        !self.key_taken && !self.open
+    }
+}
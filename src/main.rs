@@ -1,26 +1,9 @@
 #[macro_use]
 extern crate cli_log;
 
-mod achievements;
-mod args;
-mod cell_draw;
-mod dim;
-mod display;
-mod events;
-mod hof;
-mod layout;
-mod maze;
-mod nature;
-mod path;
-mod pos;
-mod pos_map;
-mod renderer;
-mod run;
-mod skin;
-mod specs;
-
 use {
     clap::Parser,
+    mazter::*,
     std::io::{
         self,
         Write,
@@ -40,34 +23,21 @@ use {
     },
 };
 
-pub use {
-    achievements::*,
-    args::*,
-    cell_draw::*,
-    dim::*,
-    display::*,
-    events::*,
-    layout::*,
-    maze::*,
-    nature::*,
-    pos::*,
-    pos_map::*,
-    renderer::*,
-    run::*,
-    skin::*,
-    specs::*,
-};
-
 /// play the game, runing level after level,
 /// in an alternate terminal
 fn play(args: &Args) -> anyhow::Result<()> {
-    let skin = Skin::build();
+    let skin = load_skin(args.theme.as_deref())?;
+    let keymap = load_keymap(args.keys.as_deref())?;
     let mut w = std::io::BufWriter::new(std::io::stderr());
     w.queue(EnterAlternateScreen)?;
     w.queue(cursor::Hide)?;
     w.queue(EnableMouseCapture)?;
     terminal::enable_raw_mode()?;
-    let r = run(&mut w, &skin, args);
+    let r = if let Some(path) = &args.replay {
+        Replay::from_file(path).and_then(|replay| run_replay(&mut w, &skin, args.charset, &replay))
+    } else {
+        run(&mut w, &skin, &keymap, args)
+    };
     w.flush()?;
     terminal::disable_raw_mode()?;
     w.queue(DisableMouseCapture)?;
@@ -79,26 +49,64 @@ fn play(args: &Args) -> anyhow::Result<()> {
 
 /// build a maze and print it on stdout
 fn build(args: &Args) -> anyhow::Result<()> {
-    let specs = if let Some(level) = args.level {
+    let mut maze = if let Some(path) = &args.load {
+        load_maze(path)?
+    } else {
+        build_specs(args)?.into()
+    };
+    if args.solution {
+        maze.highlight_path_to_exit(maze.start());
+    }
+    match args.format {
+        Format::Terminal => {
+            // the skin is only loaded when needed, as it may query the terminal,
+            // whose answer would pollute the JSON or text output
+            let skin = load_skin(args.theme.as_deref())?;
+            maze.reveal();
+            let renderer = Renderer {
+                display: Display::Standard,
+                skin: &skin,
+                charset: args.charset,
+                keymap: None,
+            };
+            renderer.write(&mut io::stdout(), &maze)
+        }
+        Format::Svg => {
+            let skin = load_skin(args.theme.as_deref())?;
+            maze.reveal();
+            write_svg(&mut io::stdout(), &maze, &skin)
+        }
+        Format::Json => MazeData::from(&maze).write_json(&mut io::stdout()),
+        Format::Text => MazeData::from(&maze).write_text(&mut io::stdout()),
+    }
+}
+
+/// compute the specs of the maze to build, according to launch arguments
+fn build_specs(args: &Args) -> anyhow::Result<Specs> {
+    let mut specs = if args.daily {
+        Specs::for_daily(Specs::daily_date())
+    } else if let Some(level) = args.level {
         let user = &args.user;
         if Database::can_play(user, level)? {
-            Specs::for_level(level)
+            let mut specs = Specs::for_level(level);
+            specs.seed = args.seed;
+            if let Some(algorithm) = args.algorithm {
+                specs.algorithm = algorithm;
+            }
+            specs
         } else {
             anyhow::bail!(
                 "User {user:?} must win the previous levels before printing level {level}"
             )
         }
     } else {
-        Specs::for_terminal_build()?
+        Specs::for_terminal_build(args.seed, args.algorithm)?
     };
+    if let Some(shape) = &args.shape {
+        specs.set_shape(Shape::from_arg(shape)?);
+    }
     debug!("specs: {:#?}", &specs);
-    let skin = Skin::build();
-    let maze: Maze = specs.into();
-    let renderer = Renderer {
-        display: Display::Standard,
-        skin: &skin,
-    };
-    renderer.write(&mut io::stdout(), &maze)
+    Ok(specs)
 }
 
 fn main() -> anyhow::Result<()> {
@@ -106,9 +114,24 @@ fn main() -> anyhow::Result<()> {
     let args = Args::parse();
     info!("launch args: {:#?}", &args);
     if args.hof {
-        hof::print()
+        if args.daily {
+            hof::print_daily(Specs::daily_date(), args.rank_by, args.timed)
+        } else {
+            match args.level {
+                Some(level) => hof::print_level(level, args.rank_by, args.timed),
+                None => hof::print(),
+            }
+        }
     } else if args.reset {
         Database::reset(&args.user, true)
+    } else if let Some(levels) = &args.simulate {
+        let stats = simulate_levels(levels, args.runs, args.seed)?;
+        if args.csv {
+            write_stats_csv(&mut io::stdout(), &stats)
+        } else {
+            print_stats_table(&stats);
+            Ok(())
+        }
     } else if args.build {
         build(&args)
     } else {
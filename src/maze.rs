@@ -2,42 +2,69 @@ use {
     crate::*,
     rand::{
         Rng,
-        thread_rng,
+        SeedableRng,
     },
+    rand_chacha::ChaCha8Rng,
     smallvec::SmallVec,
 };
 
 pub const MIN_JUMP: usize = 2;
 pub const BLAST_RADIUS: usize = 4; // must be greater than MIN_JUMP
 pub const MIN_DIM: usize = 7; // must be greater than BLAST_RADIUS + 2
+pub const WANDERER_SIGHT: usize = 8; // how far a wanderer sees the player
+pub const AMBUSH_DISTANCE: usize = 3; // how far in front of the player ambushers go
+pub const GUARD_PATROL: std::ops::RangeInclusive<usize> = 6..=14; // length of a patrol
+pub const HINTS_PER_LEVEL: usize = 3;
+pub const HINT_LENGTH: usize = 8; // how many rooms of the way a hint shows
 
 /// A maze and the state of the game (player
 /// and monster positions, etc.)
+#[derive(Clone)]
 pub struct Maze {
     pub name: String,
     pub dim: Dim,
     rooms: PosSet,
     invisible_walls: PosSet, // look like rooms, but can't teleport to them
-    openings: Vec<Pos>,      // used in growth: where it's possible to dig a new cell
     exit: Option<Pos>,
     start: Option<Pos>,
     player: Option<Pos>,
+    player_dir: Option<Dir>, // the direction of the last move of the player
     cuts: Vec<Pos>,
     highlights: PosSet,
-    monsters: Vec<Pos>,
-    turn: usize,         // a counter incremented at every end_turn
+    monsters: Vec<Monster>,
+    turn: usize, // a counter incremented at every end_turn
+    lives_lost: usize,
+    potions_drunk: usize,
+    hints_used: usize,
     next_monster: usize, // turn at which a new monster should appear
     pub lives: i32,
     monsters_period: usize,
     potions: PosSet,
-    max_monsters: usize,
+    locks: Vec<Lock>,         // doors and the keys opening them
+    spawns: Vec<MonsterKind>, // the monsters still to appear at the exit, in order
     pub default_status: &'static str,
-    squared_radius: Option<usize>,
+    /// Set by the game when the win is the best of the player on the level
+    pub personal_best: bool,
+    /// Set by the game when the loss can still be undone
+    pub rewindable: bool,
+    shape: Shape,
+    mask: PosSet,          // where rooms may be dug, according to the shape
+    vision: Option<usize>, // how far the player sees, when not the whole maze
+    seen: PosSet,          // what the player has seen at least once
+    visited: PosSet,       // where the player went, teleports included
+    /// Whether the rooms the player went through are shown
+    pub show_trail: bool,
+    clock: Option<Clock>, // in timed games, the world moves on its own
+    in_sight: PosSet,     // what the player sees now
+    seed: u64,
+    rng: ChaCha8Rng, // the source of all random decisions, initialized from the seed
 }
 impl Maze {
+    /// Create a maze with only walls, to be carved by a generator
     pub fn new<S: Into<String>>(
         name: S,
         dim: Dim,
+        seed: u64,
     ) -> Self {
         let width = dim.w;
         let height = (dim.h / 2) * 2;
@@ -46,46 +73,169 @@ impl Maze {
             dim: Dim::new(width, height),
             rooms: PosSet::new(dim, false),
             invisible_walls: PosSet::new(dim, false),
-            openings: Vec::new(),
             start: None,
             exit: None,
             player: None,
+            player_dir: None,
             cuts: Vec::new(),
             monsters: Vec::new(),
             highlights: PosSet::new(dim, false),
             turn: 0,
+            lives_lost: 0,
+            potions_drunk: 0,
+            hints_used: 0,
             next_monster: 50.min((width + height) / 3),
             lives: 1,
             monsters_period: width + height - 3,
             potions: PosSet::new(dim, false),
-            max_monsters: 10,
+            locks: Vec::new(),
+            spawns: Vec::new(),
             default_status: "",
-            squared_radius: None,
+            personal_best: false,
+            rewindable: false,
+            shape: Shape::Rectangle,
+            mask: Shape::Rectangle.mask(Dim::new(width, height)),
+            vision: None,
+            seen: PosSet::new(dim, false),
+            visited: PosSet::new(dim, false),
+            show_trail: false,
+            clock: None,
+            in_sight: PosSet::new(dim, false),
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
         }
     }
+    /// The seed from which the maze was built and with which
+    /// the random events of the game are decided
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+    /// The number of turns played
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+    pub fn lives_lost(&self) -> usize {
+        self.lives_lost
+    }
+    pub fn potions_drunk(&self) -> usize {
+        self.potions_drunk
+    }
+    pub fn hints_used(&self) -> usize {
+        self.hints_used
+    }
+    /// Set the number of hints used, which rewinding the game mustn't
+    /// take back
+    pub(crate) fn set_hints_used(
+        &mut self,
+        hints_used: usize,
+    ) {
+        self.hints_used = hints_used;
+    }
+    pub fn clock(&self) -> Option<Clock> {
+        self.clock
+    }
+    /// Make the game timed: the world moves on the clock's ticks, with
+    /// [Action::Tick], and no longer after the player's moves
+    pub fn set_clock(
+        &mut self,
+        clock: Option<Clock>,
+    ) {
+        self.clock = clock;
+    }
+    /// The time left before the level is lost, in a timed game
+    pub fn remaining_time(&self) -> Option<std::time::Duration> {
+        self.clock.map(|clock| clock.remaining(self.turn))
+    }
+    /// Where the player starts
     pub fn start(&self) -> Option<Pos> {
         self.start
     }
+    /// The room the player must reach, on the border of the maze
+    pub fn exit(&self) -> Option<Pos> {
+        self.exit
+    }
     pub fn set_start(
         &mut self,
         player: Pos,
     ) {
         self.start = Some(player);
         self.player = Some(player);
-        self.open(player);
+        self.rooms.set(player, true);
+        self.visited.set(player, true);
+    }
+    /// Set the monsters which will appear at the exit, and the period of their
+    /// appearance which depends on their number
+    fn set_spawns(
+        &mut self,
+        spawns: Vec<MonsterKind>,
+    ) {
+        let Dim { w, h } = self.dim;
+        self.monsters_period = 2 * (w * h) / (w + h);
+        self.monsters_period -= (spawns.len() * 7).min(self.monsters_period);
+        self.monsters_period = self.monsters_period.max(10);
+        self.spawns = spawns;
     }
     pub fn status(&self) -> &'static str {
-        if self.is_won() {
+        if self.is_won() && self.personal_best {
+            "New personal best! Hit any key for next level"
+        } else if self.is_won() {
             "You win. Hit any key for next level"
+        } else if self.is_lost() && self.rewindable {
+            "You lost. Undo, restart, or hit any other key to try again"
         } else if self.is_lost() {
             "You lost. Hit any key to try again"
         } else {
             self.default_status
         }
     }
+    /// The current position of the player
     pub fn player(&self) -> Option<Pos> {
         self.player
     }
+    /// The monster at the given position, if any
+    pub fn monster_at(
+        &self,
+        p: Pos,
+    ) -> Option<&Monster> {
+        self.monsters.iter().find(|monster| monster.pos == p)
+    }
+    fn has_monster(
+        &self,
+        p: Pos,
+    ) -> bool {
+        self.monster_at(p).is_some()
+    }
+    /// Tell whether there's a door at this position, which can't be
+    /// passed as the player doesn't have its key
+    pub fn is_locked(
+        &self,
+        p: Pos,
+    ) -> bool {
+        self.locks
+            .iter()
+            .any(|lock| lock.door == p && lock.is_locked())
+    }
+    fn is_door(
+        &self,
+        p: Pos,
+    ) -> bool {
+        self.locks.iter().any(|lock| lock.door == p && !lock.open)
+    }
+    fn is_key(
+        &self,
+        p: Pos,
+    ) -> bool {
+        self.locks
+            .iter()
+            .any(|lock| lock.key == p && !lock.key_taken)
+    }
+    /// The number of keys the player picked and didn't use yet
+    pub fn held_keys(&self) -> usize {
+        self.locks
+            .iter()
+            .filter(|lock| lock.key_taken && !lock.open)
+            .count()
+    }
     pub fn is_won(&self) -> bool {
         self.player == self.exit
     }
@@ -104,6 +254,7 @@ impl Maze {
     ) -> bool {
         self.rooms.get(p)
     }
+    /// End the game, lost
     pub fn give_up(&mut self) {
         self.lives = 0;
     }
@@ -113,43 +264,72 @@ impl Maze {
         &self,
         p: Pos,
     ) -> Nature {
+        if !self.is_in_sight(p) {
+            return if !self.seen.get(p) {
+                Nature::Unknown
+            } else if self.show_trail && self.visited.get(p) {
+                Nature::Visited
+            } else if self.rooms.get(p) {
+                Nature::Remembered
+            } else if self.invisible_walls.get(p) {
+                Nature::InvisibleWall
+            } else {
+                Nature::Wall
+            };
+        }
         if !self.rooms.get(p) {
             if self.invisible_walls.get(p) {
                 Nature::InvisibleWall
             } else {
                 Nature::Wall
             }
-        } else if self.monsters.contains(&p) {
-            Nature::Monster
+        } else if let Some(monster) = self.monster_at(p) {
+            Nature::Monster(monster.kind)
         } else if Some(p) == self.player {
             Nature::Player
+        } else if self.is_door(p) {
+            Nature::Door
+        } else if self.is_key(p) {
+            Nature::Key
         } else if self.potions.get(p) {
             Nature::Potion
         } else if self.highlights.get(p) {
             Nature::Highlight
+        } else if self.show_trail && self.visited.get(p) {
+            Nature::Visited
         } else {
             Nature::Room
         }
     }
-    fn center(&self) -> Pos {
-        Pos::new(self.dim.w / 2, self.dim.h / 2)
+    fn set_shape(
+        &mut self,
+        shape: Shape,
+    ) {
+        self.mask = shape.mask(self.dim);
+        self.shape = shape;
+    }
+    /// Tell whether a position can be made a room by a generator,
+    /// that is it's neither on the border nor out of the shape
+    pub(crate) fn is_diggable(
+        &self,
+        p: Pos,
+    ) -> bool {
+        p.x < self.dim.w && p.y < self.dim.h && self.mask.get(p)
     }
-    fn open(
+    pub(crate) fn dig(
         &mut self,
         p: Pos,
     ) {
         self.rooms.set(p, true);
-        let neighbours = self.inside_neighbours(p);
-        for p in neighbours {
-            if self.is_wall(p) {
-                if let Some(squared_radius) = self.squared_radius {
-                    if Pos::sq_euclidian_distance(p, self.center()) > squared_radius {
-                        continue;
-                    }
-                }
-                self.openings.push(p);
-            }
-        }
+    }
+    pub(crate) fn fill(
+        &mut self,
+        p: Pos,
+    ) {
+        self.rooms.set(p, false);
+    }
+    pub(crate) fn rng(&mut self) -> &mut ChaCha8Rng {
+        &mut self.rng
     }
     pub fn pos_in_dir(
         &self,
@@ -200,76 +380,47 @@ impl Maze {
         let Some(dest) = self.pos_in_dir(p, dir) else {
             return;
         };
-        if !self.is_room(dest) {
+        if !self.is_room(dest) || self.is_locked(dest) {
             return;
         }
+        for lock in &mut self.locks {
+            if lock.door == dest {
+                lock.open = true;
+            }
+        }
         events.add_player_move(p, dir, self.visible_nature(dest));
         self.player = Some(dest);
+        self.player_dir = Some(dir);
+        self.visited.set(dest, true);
         self.player_moved(events);
     }
-    fn seek_open(&mut self) -> bool {
-        let mut rng = thread_rng();
-        loop {
-            if self.openings.is_empty() {
-                return false;
-            }
-            let len = self.openings.len();
-            let tail = match len % 35 {
-                0 => len,
-                1 => len.min(15),
-                _ => len.min(4),
-            };
-            let idx: usize = rng.gen_range(0..tail);
-            let opening = self.openings.swap_remove(len - idx - 1);
-            let neighbours = self.inside_neighbours(opening);
-            let room_count = neighbours.iter().filter(|&p| self.is_room(*p)).count();
-            if room_count != 1 {
-                continue;
-            }
-            self.open(opening);
-            return true;
-        }
-    }
-    fn can_place_exit(&self) -> bool {
-        for x in 1..self.dim.w - 1 {
-            if self.is_room(Pos::new(x, 1)) {
-                return true;
-            }
-            if self.is_room(Pos::new(x, self.dim.h - 2)) {
-                return true;
-            }
-        }
-        for y in 1..self.dim.h - 1 {
-            if self.is_room(Pos::new(1, y)) {
-                return true;
-            }
-            if self.is_room(Pos::new(self.dim.w - 2, y)) {
-                return true;
-            }
-        }
-        false
+    pub(crate) fn can_place_exit(&self) -> bool {
+        !self.possible_exits().is_empty()
     }
-    // border walls which, when open, make an exit
+    // walls out of the shape, next to exactly one room (so that opening them
+    // doesn't make a loop), which, when open, make an exit
     fn possible_exits(&self) -> Vec<Pos> {
         let mut possible_exits = Vec::new();
-        for x in 1..self.dim.w - 1 {
-            if self.is_room(Pos::new(x, 1)) {
-                possible_exits.push(Pos::new(x, 0));
-            }
-            if self.is_room(Pos::new(x, self.dim.h - 2)) {
-                possible_exits.push(Pos::new(x, self.dim.h - 1));
-            }
-        }
-        for y in 1..self.dim.h - 1 {
-            if self.is_room(Pos::new(1, y)) {
-                possible_exits.push(Pos::new(0, y));
-            }
-            if self.is_room(Pos::new(self.dim.w - 2, y)) {
-                possible_exits.push(Pos::new(self.dim.w - 1, y));
+        for x in 0..self.dim.w {
+            for y in 0..self.dim.h {
+                let p = Pos::new(x, y);
+                if self.mask.get(p) || self.is_room(p) {
+                    continue;
+                }
+                let rooms: SmallVec<[Pos; 4]> = self
+                    .neighbours(p)
+                    .into_iter()
+                    .filter(|&n| self.is_room(n))
+                    .collect();
+                if rooms.len() == 1 && self.mask.get(rooms[0]) {
+                    possible_exits.push(p);
+                }
             }
         }
         possible_exits
     }
+    /// Return the rooms where the player may be teleported when hit
+    /// by a monster at the given position
     pub fn possible_jumps(
         &self,
         p: Pos,
@@ -286,7 +437,7 @@ impl Maze {
         for x in c.x - r..=c.x + r {
             for y in c.y - r..=c.y + r {
                 let d = Pos::new(x, y);
-                if self.is_wall(d) || self.monsters.contains(&d) {
+                if self.is_wall(d) || self.has_monster(d) || self.is_door(d) || self.is_key(d) {
                     continue;
                 }
                 if Pos::manhattan_distance(p, d) >= MIN_JUMP {
@@ -296,22 +447,27 @@ impl Maze {
         }
         possible_jumps
     }
-    fn len_to_player(
-        &self,
-        p: Pos,
-    ) -> Option<usize> {
-        self.player
-            .and_then(|player| path::find_astar(self, player, p))
-            .map(|path| path.len())
-    }
     fn try_make_exit(&mut self) {
         if let Some(exit) = self.exit {
             self.rooms.set(exit, true);
         }
+        let to_player = self
+            .player
+            .map(|player| path::DistanceMap::new(self, player));
+        // possible exits are walls, their distance is the one of the room
+        // through which they're entered
+        let len_to_player = |p: Pos| {
+            to_player.as_ref().and_then(|to_player| {
+                self.enterable_neighbours(p)
+                    .into_iter()
+                    .filter_map(|n| to_player.distance(n))
+                    .min()
+            })
+        };
         self.exit = self
             .possible_exits()
             .drain(..)
-            .max_by_key(|p| self.len_to_player(*p).unwrap_or(0));
+            .max_by_key(|p| len_to_player(*p).unwrap_or(0));
         if let Some(exit) = self.exit {
             self.rooms.set(exit, true);
         }
@@ -324,7 +480,9 @@ impl Maze {
                 if self.is_room(p)
                     && Some(p) != self.player
                     && !self.potions.get(p)
-                    && !self.monsters.contains(&p)
+                    && !self.has_monster(p)
+                    && !self.is_door(p)
+                    && !self.is_key(p)
                 {
                     empty_rooms.push(p);
                 }
@@ -337,10 +495,8 @@ impl Maze {
         for x in 1..self.dim.w - 1 {
             for y in 1..self.dim.h - 1 {
                 let p = Pos::new(x, y);
-                if let Some(squared_radius) = self.squared_radius {
-                    if Pos::sq_euclidian_distance(p, self.center()) > squared_radius {
-                        continue;
-                    }
+                if !self.mask.get(p) {
+                    continue;
                 }
                 if !self.is_wall(p) || Some(p) == self.player {
                     continue;
@@ -364,10 +520,9 @@ impl Maze {
     ) {
         debug!("adding {n} cuts");
         let mut possible_cuts = self.possible_cuts();
-        let mut rng = thread_rng();
         let mut added = 0;
         while added < n && !possible_cuts.is_empty() {
-            let idx: usize = rng.gen_range(0..possible_cuts.len());
+            let idx: usize = self.rng.gen_range(0..possible_cuts.len());
             let cut = possible_cuts.swap_remove(idx);
             self.cuts.push(cut);
             self.rooms.set(cut, true);
@@ -380,16 +535,14 @@ impl Maze {
     ) {
         debug!("adding {n} potions");
         let mut empty_rooms = self.empty_rooms();
-        let mut rng = thread_rng();
         let mut added = 0;
         while added < n && !empty_rooms.is_empty() {
-            let idx: usize = rng.gen_range(0..empty_rooms.len());
+            let idx: usize = self.rng.gen_range(0..empty_rooms.len());
             let potion = empty_rooms.swap_remove(idx);
             self.potions.set(potion, true);
             added += 1;
         }
     }
-    #[allow(dead_code)]
     fn neighbours(
         &self,
         p: Pos,
@@ -442,7 +595,7 @@ impl Maze {
         list
     }
     // (not counting the border)
-    fn inside_neighbours(
+    pub(crate) fn inside_neighbours(
         &self,
         p: Pos,
     ) -> SmallVec<[Pos; 4]> {
@@ -461,6 +614,7 @@ impl Maze {
         }
         list
     }
+    /// Return the adjacent rooms where the player or a monster may go
     pub fn enterable_neighbours(
         &self,
         p: Pos,
@@ -468,41 +622,136 @@ impl Maze {
         let mut list = SmallVec::new();
         if p.y > 0 {
             let e = Pos::new(p.x, p.y - 1);
-            if self.is_room(e) {
+            if self.is_room(e) && !self.is_locked(e) {
                 list.push(e);
             }
         }
         if p.x < self.dim.w - 1 {
             let e = Pos::new(p.x + 1, p.y);
-            if self.is_room(e) {
+            if self.is_room(e) && !self.is_locked(e) {
                 list.push(e);
             }
         }
         if p.y < self.dim.h - 1 {
             let e = Pos::new(p.x, p.y + 1);
-            if self.is_room(e) {
+            if self.is_room(e) && !self.is_locked(e) {
                 list.push(e);
             }
         }
         if p.x > 0 {
             let e = Pos::new(p.x - 1, p.y);
-            if self.is_room(e) {
+            if self.is_room(e) && !self.is_locked(e) {
                 list.push(e);
             }
         }
         list
     }
-    fn grow(
-        &mut self,
-        max: usize,
-    ) -> usize {
-        for n in 0..max {
-            let open = self.seek_open();
-            if !open {
-                return n;
+    /// Return the number of moves of the shortest way from the start to
+    /// the exit, not counting the detours to pick keys
+    pub fn optimal_moves(&self) -> Option<usize> {
+        let (start, exit) = (self.start?, self.exit?);
+        let mut maze = self.clone();
+        for lock in &mut maze.locks {
+            lock.key_taken = true;
+        }
+        path::DistanceMap::new(&maze, exit).distance(start)
+    }
+    /// Check the maze can be played, telling why it can't
+    pub fn check_playable(&self) -> anyhow::Result<()> {
+        let Some(start) = self.start else {
+            anyhow::bail!("The maze has no start");
+        };
+        let Some(exit) = self.exit else {
+            anyhow::bail!("The maze has no exit");
+        };
+        if self.mask.get(exit) {
+            anyhow::bail!("The exit isn't on the border of the maze");
+        }
+        // doors are opened as soon as their key can be reached
+        let mut maze = self.clone();
+        while path::find_astar(&maze, start, exit).is_none() {
+            let reachable_key = maze.locks.iter().position(|lock| {
+                !lock.key_taken && path::find_astar(&maze, start, lock.key).is_some()
+            });
+            let Some(idx) = reachable_key else {
+                anyhow::bail!("The exit can't be reached from the start");
+            };
+            maze.locks[idx].key_taken = true;
+        }
+        Ok(())
+    }
+    /// Set the mask of a maze read from a format which doesn't tell its
+    /// shape, the text one, from the rooms reachable from the start
+    /// without going through the exit.
+    ///
+    /// The other rooms, out of the maze, are made invisible walls.
+    pub(crate) fn mask_reachable_rooms(&mut self) {
+        let Some(start) = self.start else {
+            return;
+        };
+        let mut mask = PosSet::new(self.dim, false);
+        mask.set(start, true);
+        let mut todo = vec![start];
+        while let Some(p) = todo.pop() {
+            for n in self.neighbours(p) {
+                if self.is_room(n) && !mask.get(n) && Some(n) != self.exit {
+                    mask.set(n, true);
+                    todo.push(n);
+                }
             }
         }
-        max
+        for x in 0..self.dim.w {
+            for y in 0..self.dim.h {
+                let p = Pos::new(x, y);
+                if self.is_room(p) && !mask.get(p) && Some(p) != self.exit {
+                    self.rooms.set(p, false);
+                    self.invisible_walls.set(p, true);
+                }
+            }
+        }
+        self.mask = mask;
+    }
+    /// Check that the exit of a maze masked with [Self::mask_reachable_rooms]
+    /// is on its border: behind the exit, seen from a room of the maze,
+    /// there must be the border of the grid or a cell which is neither a
+    /// room nor a wall between two rooms.
+    pub(crate) fn check_exit_leads_out(&self) -> anyhow::Result<()> {
+        let Some(exit) = self.exit else {
+            return Ok(());
+        };
+        let is_inside = |p: Pos| {
+            let between_rooms_x = p.x > 0
+                && p.x + 1 < self.dim.w
+                && self.is_room(Pos::new(p.x - 1, p.y))
+                && self.is_room(Pos::new(p.x + 1, p.y));
+            let between_rooms_y = p.y > 0
+                && p.y + 1 < self.dim.h
+                && self.is_room(Pos::new(p.x, p.y - 1))
+                && self.is_room(Pos::new(p.x, p.y + 1));
+            self.is_room(p) || between_rooms_x || between_rooms_y
+        };
+        let leads_out = self
+            .neighbours(exit)
+            .into_iter()
+            .filter(|&n| self.mask.get(n))
+            .any(|n| match exit.in_dir(n.dir_to(exit)) {
+                Some(behind) if behind.x < self.dim.w && behind.y < self.dim.h => {
+                    !is_inside(behind)
+                }
+                _ => true,
+            });
+        if !leads_out {
+            anyhow::bail!("The exit isn't on the border of the maze");
+        }
+        Ok(())
+    }
+    /// Make invisible the rooms which can't be reached and the walls
+    /// which aren't next to a room, as is done at generation
+    ///
+    /// Warning: don't call this before the exit has been set.
+    pub(crate) fn hide_unreachable(&mut self) {
+        self.change_unreachable_rooms_into_invisible_walls();
+        self.grow_invisible_walls();
     }
     /// Due to cuts added after growing, some rooms may be unreachable
     /// in case of interrupted growing. This function makes them
@@ -511,14 +760,14 @@ impl Maze {
         let Some(exit) = self.exit else {
             return;
         };
+        let to_exit = path::DistanceMap::new(self, exit);
         for x in 0..self.dim.w {
             for y in 0..self.dim.h {
                 let pos = Pos::new(x, y);
                 if !self.rooms.get(pos) {
                     continue;
                 }
-                let path = path::find_astar(self, pos, exit);
-                if path.is_none() {
+                if to_exit.distance(pos).is_none() {
                     self.rooms.set(pos, false);
                     self.invisible_walls.set(pos, true);
                 }
@@ -579,6 +828,59 @@ impl Maze {
             self.highlights.set(start, true);
         }
     }
+    /// Return the rooms the player would walk through, destination included,
+    /// to go to the given room by the shortest path, if they may go there
+    pub fn player_path_to(
+        &self,
+        dest: Pos,
+    ) -> Option<Vec<Pos>> {
+        let player = self.player?;
+        if player == dest || !self.is_room(dest) || self.is_locked(dest) {
+            return None;
+        }
+        if Pos::sides(player, dest) {
+            return Some(vec![dest]);
+        }
+        // the path found by A* stops just before the goal
+        let mut path = path::find_astar(self, player, dest)?;
+        path.push(dest);
+        Some(path)
+    }
+    /// Highlight the next rooms of the way to the exit, or to a key when a
+    /// locked door is in the way, avoiding monsters when possible.
+    ///
+    /// Return false when the player has no hint left or no way to show
+    pub fn give_hint(&mut self) -> bool {
+        if self.hints_used >= HINTS_PER_LEVEL {
+            return false;
+        }
+        let goals: Vec<Pos> = self
+            .exit
+            .into_iter()
+            .chain(
+                self.locks
+                    .iter()
+                    .filter(|lock| !lock.key_taken)
+                    .map(|lock| lock.key),
+            )
+            .collect();
+        let mut without_monsters = self.clone();
+        for monster in &self.monsters {
+            without_monsters.rooms.set(monster.pos, false);
+        }
+        let way = goals
+            .iter()
+            .find_map(|&goal| without_monsters.player_path_to(goal))
+            .or_else(|| goals.iter().find_map(|&goal| self.player_path_to(goal)));
+        let Some(mut way) = way else {
+            return false;
+        };
+        way.truncate(HINT_LENGTH);
+        self.set_highlights(&way);
+        self.hints_used += 1;
+        true
+    }
+    /// Highlight the shortest path from the given position to the exit
     pub fn highlight_path_to_exit(
         &mut self,
         from: Option<Pos>,
@@ -593,12 +895,13 @@ impl Maze {
             self.highlights.set(exit, true);
         }
     }
-    // remove a life and teleport the player
+    /// Remove a life and teleport the player
     pub fn kill_player(
         &mut self,
         events: &mut EventList,
     ) {
         self.lives -= 1;
+        self.lives_lost += 1;
         if let Some(player) = self.player {
             if self.lives > 0 {
                 // random jump on collision
@@ -606,13 +909,14 @@ impl Maze {
                 if possible_jumps.is_empty() {
                     self.lives = 0;
                 } else {
-                    let mut rng = thread_rng();
-                    let idx = rng.gen_range(0..possible_jumps.len());
+                    let idx = self.rng.gen_range(0..possible_jumps.len());
                     let dest = possible_jumps[idx];
                     events.add_teleport(player, possible_jumps, dest);
                     self.player = Some(dest);
+                    self.visited.set(dest, true);
                     if self.potions.remove(dest) {
                         self.lives += 1;
+                        self.potions_drunk += 1;
                     }
                 }
             }
@@ -621,19 +925,40 @@ impl Maze {
         }
         debug!("Remaining lives: {}", self.lives);
     }
+    /// Apply the consequences of the player's move (fight, potion) and
+    /// end the turn
     pub fn player_moved(
         &mut self,
         events: &mut EventList,
     ) {
         if let Some(player) = self.player {
-            if self.monsters.contains(&player) {
+            if self.has_monster(player) {
                 self.kill_player(events);
             } else if self.potions.remove(player) {
                 self.lives += 1;
+                self.potions_drunk += 1;
+            }
+            for lock in &mut self.locks {
+                if lock.key == player {
+                    lock.key_taken = true;
+                }
             }
         }
-        self.end_player_turn(events);
+        if self.clock.is_none() {
+            self.end_player_turn(events);
+        }
+    }
+    /// Let the world move without moving, which is pointless in timed
+    /// games as the world moves anyway
+    pub fn wait(
+        &mut self,
+        events: &mut EventList,
+    ) {
+        if self.clock.is_none() {
+            self.end_player_turn(events);
+        }
     }
+    /// Let the bot choose and play the player's move
     pub fn move_player_auto(
         &mut self,
         events: &mut EventList,
@@ -648,11 +973,19 @@ impl Maze {
                     }
                 }
             }
-            // then we just go towards the exit
-            if let Some(path) = path::find_astar(self, player, exit) {
-                let dest = path[0];
-                if self.monsters.contains(&dest) {
-                    self.end_player_turn(events);
+            // then we just go towards the exit, or towards a key when
+            // a locked door is in the way
+            let mut goals = Some(exit).into_iter().chain(
+                self.locks
+                    .iter()
+                    .filter(|lock| !lock.key_taken)
+                    .map(|lock| lock.key),
+            );
+            let next_step =
+                goals.find_map(|goal| path::DistanceMap::new(self, goal).next_step(self, player));
+            if let Some(dest) = next_step {
+                if self.has_monster(dest) {
+                    self.wait(events);
                 } else {
                     self.try_move(player.dir_to(dest), events);
                 }
@@ -669,25 +1002,54 @@ impl Maze {
         events: &mut EventList,
     ) {
         self.turn += 1;
+        if self.clock.map_or(false, |clock| self.turn >= clock.limit) {
+            // the time is up
+            self.lives = 0;
+            return;
+        }
         if let (Some(player), Some(exit)) = (self.player, self.exit) {
+            // most monsters chase the player, one map is enough for all of them
+            let to_player = path::DistanceMap::new(self, player);
+            let mut to_ambush = None;
             for i in 0..self.monsters.len() {
-                if let Some(dir) = self.monsters[i].step_dir_to(player) {
-                    events.add_monster_move(self.monsters[i], dir, Nature::Player);
-                    self.monsters[i] = player; // monster takes the player's place
+                let (kind, pos) = (self.monsters[i].kind, self.monsters[i].pos);
+                if kind == MonsterKind::Slow && self.turn % 2 == 1 {
+                    continue;
+                }
+                if let Some(dir) = pos.step_dir_to(player) {
+                    events.add_monster_move(pos, dir, Nature::Player, kind);
+                    self.monsters[i].pos = player; // monster takes the player's place
                     self.kill_player(events);
                     break; // other monsters don't move
                 }
-                if let Some(path) = path::find_astar(self, self.monsters[i], player) {
-                    let dest = path[0];
-                    if self.monsters.contains(&dest) {
+                let dest = match kind {
+                    MonsterKind::Hunter | MonsterKind::Slow => to_player.next_step(self, pos),
+                    MonsterKind::Wanderer => self.wanderer_step(i, player, &to_player),
+                    MonsterKind::Guard => self.guard_step(i),
+                    MonsterKind::Ambusher => {
+                        if Pos::manhattan_distance(pos, player) <= AMBUSH_DISTANCE {
+                            to_player.next_step(self, pos)
+                        } else {
+                            to_ambush
+                                .get_or_insert_with(|| {
+                                    path::DistanceMap::new(self, self.ambush_target(player))
+                                })
+                                .next_step(self, pos)
+                                .or_else(|| to_player.next_step(self, pos))
+                        }
+                    }
+                };
+                if let Some(dest) = dest {
+                    if self.has_monster(dest) {
                         continue;
                     }
-                    events.add_monster_move(
-                        self.monsters[i],
-                        self.monsters[i].dir_to(dest),
-                        self.visible_nature(dest),
-                    );
-                    self.monsters[i] = dest;
+                    events.add_monster_move(pos, pos.dir_to(dest), self.visible_nature(dest), kind);
+                    let monster = &mut self.monsters[i];
+                    if monster.patrol_pos(monster.steps + 1) == Some(dest) {
+                        monster.steps += 1;
+                    }
+                    monster.came_from = Some(pos);
+                    monster.pos = dest;
                     self.potions.set(dest, false);
                     if dest == player {
                         self.kill_player(events);
@@ -695,10 +1057,11 @@ impl Maze {
                     }
                 }
             }
-            if self.monsters.len() < self.max_monsters && self.turn == self.next_monster {
-                let can_appear = exit != player && !self.monsters.contains(&exit);
+            if !self.spawns.is_empty() && self.turn == self.next_monster {
+                let can_appear = exit != player && !self.has_monster(exit);
                 if can_appear {
-                    self.monsters.push(exit);
+                    let kind = self.spawns.remove(0);
+                    self.monsters.push(Monster::new(kind, exit));
                 } else {
                     self.next_monster += 1;
                 }
@@ -712,6 +1075,263 @@ impl Maze {
                 }
             }
         }
+        self.look();
+    }
+    /// Choose the move of a wanderer: random until it sees the player
+    fn wanderer_step(
+        &mut self,
+        i: usize,
+        player: Pos,
+        to_player: &path::DistanceMap,
+    ) -> Option<Pos> {
+        let pos = self.monsters[i].pos;
+        if !self.monsters[i].alerted
+            && Pos::sq_euclidian_distance(pos, player) <= WANDERER_SIGHT * WANDERER_SIGHT
+            && (self.is_line_clear(pos, player) || self.is_line_clear(player, pos))
+        {
+            self.monsters[i].alerted = true;
+        }
+        if self.monsters[i].alerted {
+            return to_player.next_step(self, pos);
+        }
+        let mut dests = self.enterable_neighbours(pos);
+        if dests.len() > 1 {
+            // it only walks back when in a dead end
+            dests.retain(|p| Some(*p) != self.monsters[i].came_from);
+        }
+        if dests.is_empty() {
+            None
+        } else {
+            Some(dests[self.rng.gen_range(0..dests.len())])
+        }
+    }
+    /// Choose the move of a guard: the next room of its patrol, or the
+    /// way back to its patrol when it left it to hit the player
+    fn guard_step(
+        &self,
+        i: usize,
+    ) -> Option<Pos> {
+        let guard = &self.monsters[i];
+        let next = guard.patrol_pos(guard.steps + 1)?;
+        if Pos::sides(guard.pos, next) {
+            Some(next)
+        } else {
+            let current = guard.patrol_pos(guard.steps)?;
+            path::DistanceMap::new(self, current).next_step(self, guard.pos)
+        }
+    }
+    /// The room ambushers head to: a few steps in front of the player
+    fn ambush_target(
+        &self,
+        player: Pos,
+    ) -> Pos {
+        let mut target = player;
+        if let Some(dir) = self.player_dir {
+            for _ in 0..AMBUSH_DISTANCE {
+                match self.pos_in_dir(target, dir) {
+                    Some(p) if self.is_room(p) => target = p,
+                    _ => break,
+                }
+            }
+        }
+        target
+    }
+    /// Add locked doors on the way from the start to the exit, each with
+    /// a key which can be reached without passing its door
+    fn add_locks(
+        &mut self,
+        n: usize,
+    ) {
+        debug!("adding {n} locks");
+        let (Some(start), Some(exit)) = (self.start, self.exit) else {
+            return;
+        };
+        let Some(path) = path::find_astar(self, start, exit) else {
+            return;
+        };
+        // doors are put in corridors, so that they really block them
+        let corridors: Vec<Pos> = path
+            .iter()
+            .copied()
+            .filter(|&p| {
+                p != exit && !self.potions.get(p) && self.enterable_neighbours(p).len() == 2
+            })
+            .collect();
+        for i in 0..n {
+            // the doors are spread along the path, the first one
+            // being the closest to the start
+            let target = corridors.len() * (i + 1) / (n + 1);
+            let Some(&door) = corridors.get(target) else {
+                break;
+            };
+            // but a door is better where the exit can't be reached by
+            // another way, which isn't always possible due to cuts
+            let mut doors: Vec<(usize, Pos)> = corridors.iter().copied().enumerate().collect();
+            doors.sort_by_key(|&(idx, _)| (idx as isize - target as isize).abs());
+            let door = doors
+                .into_iter()
+                .map(|(_, p)| p)
+                .take(20)
+                .find(|&p| {
+                    self.locks.push(Lock::new(p, p));
+                    let blocking = path::find_astar(self, start, exit).is_none();
+                    self.locks.pop();
+                    blocking
+                })
+                .unwrap_or(door);
+            let mut candidates: Vec<Pos> = self
+                .empty_rooms()
+                .into_iter()
+                .filter(|p| !path.contains(p))
+                .collect();
+            // while looking for its key, the door is locked, and the keys of
+            // the previous doors are supposed picked
+            self.locks.push(Lock::new(door, door));
+            let key = loop {
+                if candidates.is_empty() {
+                    break None;
+                }
+                let idx = self.rng.gen_range(0..candidates.len());
+                let key = candidates.swap_remove(idx);
+                if path::find_astar(self, start, key).is_some() {
+                    break Some(key);
+                }
+            };
+            let Some(key) = key else {
+                warn!("no place for the key of door {door:?}");
+                self.locks.pop();
+                break;
+            };
+            self.locks[i].key = key;
+            // the next keys may be behind this door
+            self.locks[i].key_taken = true;
+        }
+        for lock in &mut self.locks {
+            lock.key_taken = false;
+        }
+    }
+    /// Add guards, with their patrols, in rooms far enough from the player
+    fn add_guards(
+        &mut self,
+        n: usize,
+    ) {
+        debug!("adding {n} guards");
+        let Some(player) = self.player else {
+            return;
+        };
+        let to_player = path::DistanceMap::new(self, player);
+        let mut homes = self.empty_rooms();
+        homes.retain(|&p| {
+            to_player
+                .distance(p)
+                .map_or(false, |d| d > *GUARD_PATROL.end())
+        });
+        let mut added = 0;
+        while added < n && !homes.is_empty() {
+            let idx = self.rng.gen_range(0..homes.len());
+            let home = homes.swap_remove(idx);
+            let from_home = path::DistanceMap::new(self, home);
+            let ends: Vec<Pos> = homes
+                .iter()
+                .copied()
+                .filter(|&p| {
+                    from_home
+                        .distance(p)
+                        .map_or(false, |d| GUARD_PATROL.contains(&d))
+                })
+                .collect();
+            let end = if ends.is_empty() {
+                // no room at the distance of a patrol, the guard walks to the
+                // farthest room it can within this distance
+                (0..self.dim.w)
+                    .flat_map(|x| (0..self.dim.h).map(move |y| Pos::new(x, y)))
+                    .filter(|&p| Some(p) != self.exit)
+                    .filter_map(|p| from_home.distance(p).map(|d| (d, p)))
+                    .filter(|&(d, _)| d > 0 && d <= *GUARD_PATROL.end())
+                    .max_by_key(|&(d, _)| d)
+                    .map(|(_, p)| p)
+            } else {
+                Some(ends[self.rng.gen_range(0..ends.len())])
+            };
+            let mut guard = Monster::new(MonsterKind::Guard, home);
+            if let Some(mut p) = end {
+                // the patrol is the shortest path from the end to the home, reversed
+                guard.patrol.push(p);
+                while let Some(next) = from_home.next_step(self, p) {
+                    guard.patrol.push(next);
+                    p = next;
+                }
+                guard.patrol.reverse();
+            }
+            self.monsters.push(guard);
+            added += 1;
+        }
+    }
+    /// Update what the player sees and remembers, when the vision is limited
+    fn look(&mut self) {
+        let (Some(radius), Some(player)) = (self.vision, self.player) else {
+            return;
+        };
+        self.in_sight.clear();
+        let x_range = player.x.saturating_sub(radius)..=(player.x + radius).min(self.dim.w - 1);
+        let y_range = player.y.saturating_sub(radius)..=(player.y + radius).min(self.dim.h - 1);
+        for x in x_range {
+            for y in y_range.clone() {
+                let p = Pos::new(x, y);
+                if Pos::sq_euclidian_distance(p, player) > radius * radius {
+                    continue;
+                }
+                if self.is_line_clear(player, p) || self.is_line_clear(p, player) {
+                    self.in_sight.set(p, true);
+                    self.seen.set(p, true);
+                }
+            }
+        }
+    }
+    /// Tell whether there's no wall on the straight line from `a` to `b`,
+    /// both ends excluded
+    fn is_line_clear(
+        &self,
+        a: Pos,
+        b: Pos,
+    ) -> bool {
+        // Bresenham's line algorithm
+        let (x0, y0) = (a.x as i64, a.y as i64);
+        let (x1, y1) = (b.x as i64, b.y as i64);
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let (mut x, mut y) = (x0, y0);
+        let mut err = dx + dy;
+        loop {
+            if x == x1 && y == y1 {
+                return true;
+            }
+            if (x, y) != (x0, y0) && self.is_wall(Pos::new(x as usize, y as usize)) {
+                return false;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+    /// Tell whether the player currently sees the position
+    pub fn is_in_sight(
+        &self,
+        p: Pos,
+    ) -> bool {
+        self.vision.is_none() || self.in_sight.get(p)
+    }
+    /// Make the whole maze visible, for example at the end of the game
+    pub fn reveal(&mut self) {
+        self.vision = None;
     }
 }
 
@@ -719,59 +1339,148 @@ impl From<Specs> for Maze {
     fn from(specs: Specs) -> Self {
         let width = specs.dim.w;
         let height = specs.dim.h;
-        let mut maze = Self::new(&specs.name, specs.dim);
-        if specs.disk {
-            let d = width.min(height) / 2;
-            if d > 10 {
-                maze.squared_radius = Some((d + 1) * (d + 1));
-            }
-        }
+        let seed = specs.seed.unwrap_or_else(random_seed);
+        let mut maze = Self::new(&specs.name, specs.dim, seed);
+        maze.set_shape(specs.shape);
         maze.lives = specs.lives;
-        let mut rng = thread_rng();
-        loop {
-            let start = Pos::new(
-                rng.gen_range(width / 6..width * 5 / 6),
-                rng.gen_range(height / 6..height * 5 / 6),
-            );
-            if let Some(squared_radius) = maze.squared_radius {
-                if Pos::sq_euclidian_distance(start, maze.center()) + 2 > squared_radius {
-                    // this isn't a valid starting position: we might be unable to grow
-                    // from there
+        // The start is chosen on a cell of the grid the classic algorithms
+        // work on (odd coordinates), with room around to grow from there,
+        // and preferably not too close from the borders
+        let mut candidates = Vec::new();
+        let mut central_candidates = Vec::new();
+        for x in (1..width - 1).step_by(2) {
+            for y in (1..maze.dim.h - 1).step_by(2) {
+                let p = Pos::new(x, y);
+                if !maze.is_diggable(p) || !maze.neighbours(p).iter().all(|&n| maze.is_diggable(n))
+                {
                     continue;
                 }
-            }
-            maze.set_start(start);
-            break;
-        }
-        if specs.fill {
-            while maze.grow(10) > 0 {}
-        } else {
-            let n = (width * height) / 3;
-            loop {
-                info!("growing 1");
-                if maze.grow(n) == 0 {
-                    break;
-                }
-                if maze.can_place_exit() {
-                    break;
+                candidates.push(p);
+                if (width / 6..width * 5 / 6).contains(&x)
+                    && (height / 6..height * 5 / 6).contains(&y)
+                {
+                    central_candidates.push(p);
                 }
             }
         }
+        if !central_candidates.is_empty() {
+            candidates = central_candidates;
+        }
+        let start = if candidates.is_empty() {
+            warn!("no valid start in the shape");
+            // any position of the shape is better than one out of it
+            (0..height)
+                .flat_map(|y| (0..width).map(move |x| Pos::new(x, y)))
+                .find(|&p| maze.is_diggable(p))
+                .unwrap_or(Pos::new(1, 1))
+        } else {
+            candidates[maze.rng.gen_range(0..candidates.len())]
+        };
+        maze.set_start(start);
+        debug!("carving with {}", specs.algorithm);
+        specs.algorithm.generator().carve(&mut maze, specs.fill);
         maze.add_cuts(specs.cuts);
         maze.add_potions(specs.potions);
         maze.try_make_exit();
         maze.grow_invisible_walls();
         maze.change_unreachable_rooms_into_invisible_walls();
+        maze.add_locks(specs.doors);
         maze.default_status = specs.status;
-        debug!("squared_radius: {:?}", maze.squared_radius);
-        maze.max_monsters = specs.monsters;
-        maze.monsters_period = 2 * (width * height) / (width + height);
-        maze.monsters_period -= (maze.max_monsters * 7).min(maze.monsters_period);
-        maze.monsters_period = maze.monsters_period.max(10);
+        let (guards, spawns): (Vec<MonsterKind>, Vec<MonsterKind>) = specs
+            .monsters
+            .iter()
+            .partition(|&&kind| kind == MonsterKind::Guard);
+        maze.add_guards(guards.len());
+        maze.set_spawns(spawns);
+        maze.vision = specs.vision;
+        maze.look();
         maze
     }
 }
 
+impl From<&Maze> for MazeData {
+    fn from(maze: &Maze) -> Self {
+        Self {
+            name: maze.name.clone(),
+            seed: maze.seed,
+            dim: maze.dim,
+            shape: maze.shape.clone(),
+            vision: maze.vision,
+            lives: maze.lives,
+            spawns: maze.spawns.clone(),
+            start: maze.start,
+            exit: maze.exit,
+            rooms: maze.rooms.positions(),
+            invisible_walls: maze.invisible_walls.positions(),
+            cuts: maze.cuts.clone(),
+            potions: maze.potions.positions(),
+            locks: maze.locks.clone(),
+            monsters: maze.monsters.clone(),
+        }
+    }
+}
+
+impl TryFrom<MazeData> for Maze {
+    type Error = anyhow::Error;
+    fn try_from(data: MazeData) -> anyhow::Result<Self> {
+        let mut maze = Self::new(data.name, data.dim, data.seed);
+        if maze.dim.w < MIN_DIM || maze.dim.h < MIN_DIM {
+            anyhow::bail!("The maze is too small");
+        }
+        let monster_positions: Vec<Pos> = data
+            .monsters
+            .iter()
+            .flat_map(|monster| monster.patrol.iter().chain(Some(&monster.pos)))
+            .copied()
+            .chain(data.locks.iter().flat_map(|lock| [lock.door, lock.key]))
+            .collect();
+        let out = [
+            &data.rooms,
+            &data.invisible_walls,
+            &data.cuts,
+            &data.potions,
+            &monster_positions,
+        ]
+        .into_iter()
+        .flatten()
+        .chain(data.start.iter())
+        .chain(data.exit.iter())
+        .find(|p| p.x >= maze.dim.w || p.y >= maze.dim.h);
+        if let Some(p) = out {
+            anyhow::bail!(
+                "Position {p:?} is out of the {}x{} maze",
+                maze.dim.w,
+                maze.dim.h
+            );
+        }
+        maze.set_shape(data.shape);
+        maze.lives = data.lives;
+        for p in data.rooms.into_iter().chain(data.cuts.iter().copied()) {
+            maze.rooms.set(p, true);
+        }
+        for p in data.invisible_walls {
+            maze.invisible_walls.set(p, true);
+        }
+        for p in data.potions {
+            maze.potions.set(p, true);
+        }
+        if let Some(start) = data.start {
+            maze.set_start(start);
+        }
+        if let Some(exit) = data.exit {
+            maze.rooms.set(exit, true);
+            maze.exit = Some(exit);
+        }
+        maze.cuts = data.cuts;
+        maze.locks = data.locks;
+        maze.monsters = data.monsters;
+        maze.set_spawns(data.spawns);
+        maze.vision = data.vision;
+        maze.look();
+        Ok(maze)
+    }
+}
+
 #[test]
 fn show() {
     for i in 1..500 {
@@ -784,3 +1493,158 @@ fn show() {
     }
     todo!();
 }
+
+#[test]
+fn same_seed_same_maze() {
+    let mut specs = Specs::for_level(27);
+    specs.seed = Some(1234);
+    let a = Maze::from(specs.clone());
+    let b = Maze::from(specs);
+    for x in 0..a.dim.w {
+        for y in 0..a.dim.h {
+            let p = Pos::new(x, y);
+            assert_eq!(a.visible_nature(p), b.visible_nature(p));
+        }
+    }
+}
+
+#[test]
+fn limited_vision() {
+    let mut specs = Specs::for_level(21);
+    specs.seed = Some(3);
+    let vision = specs.vision.unwrap();
+    let mut maze = Maze::from(specs);
+    let player = maze.player().unwrap();
+    for x in 0..maze.dim.w {
+        for y in 0..maze.dim.h {
+            let p = Pos::new(x, y);
+            if Pos::sq_euclidian_distance(p, player) > vision * vision {
+                assert_eq!(maze.visible_nature(p), Nature::Unknown);
+            }
+        }
+    }
+    maze.reveal();
+    assert!(maze.is_in_sight(Pos::new(0, 0)));
+}
+
+#[test]
+fn guards_patrol() {
+    let mut specs = Specs::for_level(36);
+    specs.seed = Some(5);
+    let mut maze = Maze::from(specs);
+    let mut events = EventList::default();
+    for _ in 0..60 {
+        Action::Wait.apply(&mut maze, &mut events);
+        events.clear();
+        for guard in maze
+            .monsters
+            .iter()
+            .filter(|m| m.kind == MonsterKind::Guard)
+        {
+            assert!(guard.patrol.len() > 1);
+            assert!(guard.patrol.contains(&guard.pos));
+        }
+    }
+}
+
+#[test]
+fn keys_can_be_reached() {
+    for level in (15..200).step_by(6) {
+        let mut specs = Specs::for_level(level);
+        specs.seed = Some(level as u64);
+        let maze = Maze::from(specs);
+        assert!(!maze.locks.is_empty(), "no lock in level {level}");
+        maze.check_playable().unwrap();
+    }
+}
+
+#[test]
+fn player_walks_to_a_room() {
+    let mut specs = Specs::for_level(12);
+    specs.seed = Some(42);
+    let maze = Maze::from(specs);
+    let exit = maze.exit().unwrap();
+    let path = maze.player_path_to(exit).unwrap();
+    assert_eq!(path.last(), Some(&exit));
+    let mut pos = maze.player().unwrap();
+    for &step in &path {
+        assert!(Pos::sides(pos, step));
+        assert!(maze.is_room(step));
+        pos = step;
+    }
+}
+
+#[test]
+fn hints_are_limited() {
+    let mut specs = Specs::for_level(12);
+    specs.seed = Some(42);
+    let mut maze = Maze::from(specs);
+    for _ in 0..HINTS_PER_LEVEL {
+        assert!(maze.give_hint());
+    }
+    assert!(!maze.give_hint());
+    assert_eq!(maze.hints_used(), HINTS_PER_LEVEL);
+    let next = maze.player().unwrap();
+    let highlighted = maze
+        .enterable_neighbours(next)
+        .into_iter()
+        .filter(|&p| maze.visible_nature(p) == Nature::Highlight)
+        .count();
+    assert_eq!(highlighted, 1);
+}
+
+#[test]
+fn trail_survives_teleports() {
+    let mut specs = Specs::for_level(12);
+    specs.seed = Some(42);
+    let mut maze = Maze::from(specs);
+    let start = maze.start().unwrap();
+    let first_step = maze.player_path_to(maze.exit().unwrap()).unwrap()[0];
+    let mut events = EventList::default();
+    maze.try_move(start.dir_to(first_step), &mut events);
+    maze.lives = 3;
+    maze.kill_player(&mut events);
+    assert_ne!(maze.player(), Some(first_step));
+    assert!(maze.visited.get(maze.player().unwrap()));
+    assert_eq!(maze.visible_nature(start), Nature::Room);
+    maze.show_trail = true;
+    assert_eq!(maze.visible_nature(start), Nature::Visited);
+}
+
+#[test]
+fn timed_games_are_lost_when_the_time_is_up() {
+    let mut specs = Specs::for_level(3);
+    specs.seed = Some(42);
+    let mut maze = Maze::from(specs);
+    let clock = Clock::new(&maze, 300);
+    maze.set_clock(Some(clock));
+    let mut events = EventList::default();
+    // moving doesn't make the world move
+    Action::Auto.apply(&mut maze, &mut events);
+    assert_eq!(maze.turn(), 0);
+    for _ in 0..clock.limit {
+        Action::Tick.apply(&mut maze, &mut events);
+        events.clear();
+    }
+    assert_eq!(maze.remaining_time(), Some(std::time::Duration::ZERO));
+    assert!(maze.is_lost());
+}
+
+#[test]
+fn guards_always_patrol() {
+    let mut guards = 0;
+    for level in [36, 40, 44] {
+        for seed in 0..5 {
+            let mut specs = Specs::for_level(level);
+            specs.seed = Some(seed);
+            let maze = Maze::from(specs);
+            for monster in MazeData::from(&maze).monsters {
+                if monster.kind == MonsterKind::Guard {
+                    assert!(!monster.patrol.is_empty(), "level {level} seed {seed}");
+                    guards += 1;
+                }
+            }
+        }
+    }
+    assert!(guards > 0);
+}
@@ -0,0 +1,174 @@
+use {
+    crate::*,
+    std::{
+        fs,
+        io::Write,
+        path::Path,
+    },
+};
+
+/// A serializable description of a maze, enough to play it again.
+///
+/// Conversions from and to a [Maze] are defined in the maze module.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MazeData {
+    pub name: String,
+    pub seed: u64,
+    pub dim: Dim,
+    pub shape: Shape,
+    /// How far the player sees, when not the whole maze
+    #[serde(default)]
+    pub vision: Option<usize>,
+    pub lives: i32,
+    /// The monsters which will appear at the exit, in order
+    #[serde(default)]
+    pub spawns: Vec<MonsterKind>,
+    pub start: Option<Pos>,
+    pub exit: Option<Pos>,
+    pub rooms: Vec<Pos>,
+    /// Walls looking like rooms, out of the maze
+    pub invisible_walls: Vec<Pos>,
+    /// Walls opened after carving, making loops
+    pub cuts: Vec<Pos>,
+    pub potions: Vec<Pos>,
+    /// Locked doors and their keys
+    #[serde(default)]
+    pub locks: Vec<Lock>,
+    pub monsters: Vec<Monster>,
+}
+
+impl MazeData {
+    pub fn write_json<W: Write>(
+        &self,
+        w: &mut W,
+    ) -> anyhow::Result<()> {
+        serde_json::to_writer_pretty(&mut *w, self)?;
+        writeln!(w)?;
+        Ok(())
+    }
+    pub fn from_json_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+/// Read a maze from a JSON file or, when the extension isn't `json`,
+/// from a text file, and check it can be played
+pub fn load_maze<P: AsRef<Path>>(path: P) -> anyhow::Result<Maze> {
+    let path = path.as_ref();
+    let is_json = path.extension().map_or(false, |ext| ext == "json");
+    let data = if is_json {
+        MazeData::from_json_file(path)?
+    } else {
+        MazeData::from_text(&fs::read_to_string(path)?)?
+    };
+    let mut maze = Maze::try_from(data)?;
+    if !is_json {
+        // the text format doesn't tell the shape of the maze
+        maze.mask_reachable_rooms();
+    }
+    maze.check_playable()?;
+    if !is_json {
+        maze.check_exit_leads_out()?;
+        // the text format doesn't tell rooms from invisible walls
+        maze.hide_unreachable();
+    }
+    Ok(maze)
+}
+
+#[test]
+fn json_round_trip() {
+    let mut specs = Specs::for_level(23);
+    specs.seed = Some(42);
+    let maze = Maze::from(specs);
+    let data = MazeData::from(&maze);
+    let mut json = Vec::new();
+    data.write_json(&mut json).unwrap();
+    let read: MazeData = serde_json::from_slice(&json).unwrap();
+    let loaded = Maze::try_from(read).unwrap();
+    assert_eq!(MazeData::from(&loaded), data);
+}
+
+#[test]
+fn exports_can_be_loaded() {
+    let mut specs = Specs::for_level(12);
+    specs.seed = Some(42);
+    let maze = Maze::from(specs);
+    let data = MazeData::from(&maze);
+    let dir = std::env::temp_dir().join(format!("mazter-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let json_path = dir.join("maze.json");
+    data.write_json(&mut fs::File::create(&json_path).unwrap())
+        .unwrap();
+    let loaded = load_maze(&json_path).unwrap();
+    assert_eq!(MazeData::from(&loaded), data);
+    let text_path = dir.join("maze.txt");
+    data.write_text(&mut fs::File::create(&text_path).unwrap())
+        .unwrap();
+    let loaded = load_maze(&text_path).unwrap();
+    assert_eq!(loaded.start(), maze.start());
+    assert_eq!(loaded.exit(), maze.exit());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn text_exports_of_all_shapes_can_be_loaded() {
+    let mut shapes: Vec<Shape> = SHAPE_NAMES
+        .iter()
+        .map(|name| Shape::from_arg(name).unwrap())
+        .collect();
+    shapes.push(Shape::Banner("MAZE".to_string()));
+    shapes.push(Shape::Drawing(vec![
+        " ##### ".to_string(),
+        "#######".to_string(),
+        "  ###  ".to_string(),
+    ]));
+    let dir = std::env::temp_dir().join(format!("mazter-shapes-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("maze.txt");
+    for shape in shapes {
+        for seed in 0..5 {
+            let mut specs = Specs::for_level(20);
+            specs.seed = Some(seed);
+            specs.set_shape(shape.clone());
+            let maze = Maze::from(specs);
+            let data = MazeData::from(&maze);
+            data.write_text(&mut fs::File::create(&path).unwrap())
+                .unwrap();
+            let loaded = load_maze(&path)
+                .unwrap_or_else(|e| panic!("{shape:?} with seed {seed} can't be loaded: {e}"));
+            assert_eq!(loaded.exit(), maze.exit());
+            let mut rooms = MazeData::from(&loaded).rooms;
+            let mut expected = data.rooms.clone();
+            rooms.sort_by_key(|p| (p.y, p.x));
+            expected.sort_by_key(|p| (p.y, p.x));
+            assert_eq!(rooms, expected, "{shape:?} with seed {seed}");
+        }
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn text_mazes_need_their_exit_on_the_border() {
+    let text = "\
+#########
+#S      #
+# ##### #
+#   #   #
+### # # #
+#   # # #
+# ### # #
+#######E#
+";
+    let dir = std::env::temp_dir().join(format!("mazter-exit-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("maze.txt");
+    fs::write(&path, text).unwrap();
+    assert!(load_maze(&path).is_ok());
+    let inner_exit = text
+        .replace("#######E#", "#########")
+        .replace("#   #   #", "#   E   #");
+    fs::write(&path, inner_exit).unwrap();
+    assert!(load_maze(&path).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,144 @@
+use {
+    crate::*,
+    anyhow::bail,
+    std::io::Write,
+};
+
+// The text format of a maze is a grid of characters, one per cell:
+//
+// - `#` : a wall
+// - ` ` : a room (cells out of the maze are written as rooms too)
+// - `S` : the start
+// - `E` : the exit
+// - `+` : a potion
+// - `M` : a monster (read as a hunter, whatever its kind when written)
+// - `D` : a locked door
+// - `K` : a key, opening the door of the same rank in reading order
+
+impl MazeData {
+    /// Write the maze as a grid of characters
+    pub fn write_text<W: Write>(
+        &self,
+        w: &mut W,
+    ) -> anyhow::Result<()> {
+        let mut grid = vec![vec!['#'; self.dim.w]; self.dim.h];
+        let mut put = |p: Pos, c: char| grid[p.y][p.x] = c;
+        for &p in self.rooms.iter().chain(&self.invisible_walls) {
+            put(p, ' ');
+        }
+        for &p in &self.potions {
+            put(p, '+');
+        }
+        for monster in &self.monsters {
+            put(monster.pos, 'M');
+        }
+        for lock in self.locks.iter().filter(|lock| lock.is_locked()) {
+            put(lock.door, 'D');
+            put(lock.key, 'K');
+        }
+        if let Some(start) = self.start {
+            put(start, 'S');
+        }
+        if let Some(exit) = self.exit {
+            put(exit, 'E');
+        }
+        for line in grid {
+            writeln!(w, "{}", line.into_iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+    /// Read a maze written as a grid of characters.
+    ///
+    /// Lines shorter than the longest one are completed with walls.
+    pub fn from_text(text: &str) -> anyhow::Result<Self> {
+        let lines: Vec<&str> = text.lines().collect();
+        let w = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let h = lines.len();
+        if w < MIN_DIM || h < MIN_DIM {
+            bail!("The maze must be at least {MIN_DIM}x{MIN_DIM}");
+        }
+        if h % 2 == 1 {
+            bail!("The maze must have an even number of lines");
+        }
+        let mut data = Self {
+            name: "Custom maze".to_string(),
+            seed: random_seed(),
+            dim: Dim::new(w, h),
+            shape: Shape::Rectangle,
+            vision: None,
+            lives: 1,
+            spawns: Vec::new(),
+            start: None,
+            exit: None,
+            rooms: Vec::new(),
+            invisible_walls: Vec::new(),
+            cuts: Vec::new(),
+            potions: Vec::new(),
+            locks: Vec::new(),
+            monsters: Vec::new(),
+        };
+        let mut doors = Vec::new();
+        let mut keys = Vec::new();
+        for (y, line) in lines.iter().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let p = Pos::new(x, y);
+                match c {
+                    '#' => {
+                        continue;
+                    }
+                    ' ' => {}
+                    'S' => {
+                        if data.start.is_some() {
+                            bail!("Several starts, the second one at line {}", y + 1);
+                        }
+                        data.start = Some(p);
+                    }
+                    'E' => {
+                        if data.exit.is_some() {
+                            bail!("Several exits, the second one at line {}", y + 1);
+                        }
+                        data.exit = Some(p);
+                    }
+                    '+' => data.potions.push(p),
+                    'M' => data.monsters.push(Monster::new(MonsterKind::Hunter, p)),
+                    'D' => doors.push(p),
+                    'K' => keys.push(p),
+                    _ => bail!("Unexpected {c:?} at line {}, column {}", y + 1, x + 1),
+                }
+                data.rooms.push(p);
+            }
+        }
+        if doors.len() != keys.len() {
+            bail!("{} doors but {} keys", doors.len(), keys.len());
+        }
+        data.locks = doors
+            .into_iter()
+            .zip(keys)
+            .map(|(door, key)| Lock::new(door, key))
+            .collect();
+        Ok(data)
+    }
+}
+
+#[test]
+fn unreachable_exit() {
+    let text = "\
+#########
+#S  #   #
+# # # # #
+# # # # #
+# #   # #
+# ##### #
+#   #  ##
+#######E#
+";
+    let maze = Maze::try_from(MazeData::from_text(text).unwrap()).unwrap();
+    assert!(maze.check_playable().is_err());
+    let maze =
+        Maze::try_from(MazeData::from_text(&text.replace("#  ##", "#   #")).unwrap()).unwrap();
+    assert!(maze.check_playable().is_ok());
+}
@@ -0,0 +1,67 @@
+use crate::*;
+
+/// The species of a monster, which decides how it moves
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MonsterKind {
+    /// Chases the player by the shortest path
+    Hunter,
+    /// Chases the player too, but moves only every other turn
+    Slow,
+    /// Moves randomly until it sees the player, then chases them
+    Wanderer,
+    /// Walks back and forth along a fixed patrol, from the start of the game
+    Guard,
+    /// Heads for the room in front of the player, to cut their way
+    Ambusher,
+}
+
+/// A monster in the maze, with the memory its kind needs
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Monster {
+    pub kind: MonsterKind,
+    pub pos: Pos,
+    /// The rooms a guard walks along, back and forth
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patrol: Vec<Pos>,
+    /// How far a guard went in its patrol, round trips included
+    #[serde(default)]
+    pub steps: usize,
+    /// Whether a wanderer has seen the player, and now chases them
+    #[serde(default)]
+    pub alerted: bool,
+    /// The previous position, so that a wanderer doesn't walk back
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub came_from: Option<Pos>,
+}
+
+impl Monster {
+    pub fn new(
+        kind: MonsterKind,
+        pos: Pos,
+    ) -> Self {
+        Self {
+            kind,
+            pos,
+            patrol: Vec::new(),
+            steps: 0,
+            alerted: false,
+            came_from: None,
+        }
+    }
+    /// Return the position in the patrol after the given number of steps
+    pub fn patrol_pos(
+        &self,
+        steps: usize,
+    ) -> Option<Pos> {
+        match self.patrol.len() {
+            0 => None,
+            1 => Some(self.patrol[0]),
+            len => {
+                let round_trip = 2 * (len - 1);
+                let i = steps % round_trip;
+                Some(self.patrol[if i < len { i } else { round_trip - i }])
+            }
+        }
+    }
+}
@@ -1,10 +1,22 @@
+use crate::*;
+
+/// What a cell of the maze looks like
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Nature {
     Room,
     Wall,
     InvisibleWall,
     Player,
-    Monster,
+    Monster(MonsterKind),
     Potion,
+    /// A locked door, or a door whose key was picked
+    Door,
+    Key,
     Highlight,
+    /// Never seen by the player, when the vision is limited
+    Unknown,
+    /// A room seen before but out of sight now
+    Remembered,
+    /// A room the player went through, when the trail is shown
+    Visited,
 }
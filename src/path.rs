@@ -1,6 +1,9 @@
 use {
     crate::*,
-    std::collections::BinaryHeap,
+    std::collections::{
+        BinaryHeap,
+        VecDeque,
+    },
 };
 
 /// Find a short path between start and goal using A*.
@@ -58,3 +61,84 @@ pub fn find_astar(
     // open_set is empty, there's no path
     None
 }
+
+/// The distances to a goal of all the rooms from which it can be
+/// reached (a "Dijkstra map").
+///
+/// It's computed once, in a time proportional to the size of the maze,
+/// and can then be shared by all the walkers heading to the same goal.
+pub struct DistanceMap {
+    distances: PosMap<usize>,
+}
+
+impl DistanceMap {
+    pub fn new(
+        maze: &Maze,
+        goal: Pos,
+    ) -> Self {
+        let mut distances = PosMap::new(maze.dim, usize::MAX);
+        distances.set(goal, 0);
+        // all steps have the same cost, so Dijkstra is a breadth-first search
+        let mut queue = VecDeque::new();
+        queue.push_back(goal);
+        while let Some(current) = queue.pop_front() {
+            let d = distances.get(current) + 1;
+            for neighbour in maze.enterable_neighbours(current) {
+                if distances.get(neighbour) == usize::MAX {
+                    distances.set(neighbour, d);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        Self { distances }
+    }
+    /// Return the number of steps from p to the goal, if it can be reached
+    pub fn distance(
+        &self,
+        p: Pos,
+    ) -> Option<usize> {
+        let d = self.distances.get(p);
+        if d == usize::MAX { None } else { Some(d) }
+    }
+    /// Return the first step of a shortest path from p to the goal
+    pub fn next_step(
+        &self,
+        maze: &Maze,
+        p: Pos,
+    ) -> Option<Pos> {
+        let d = self.distance(p)?;
+        maze.enterable_neighbours(p)
+            .into_iter()
+            .filter(|&n| self.distances.get(n) < d)
+            .min_by_key(|&n| self.distances.get(n))
+    }
+}
+
+#[test]
+fn distance_map_matches_astar() {
+    let mut specs = Specs::for_level(27);
+    specs.seed = Some(42);
+    let maze = Maze::from(specs);
+    assert!(
+        MazeData::from(&maze)
+            .locks
+            .iter()
+            .any(|lock| lock.is_locked())
+    );
+    let exit = maze.exit().unwrap();
+    let map = DistanceMap::new(&maze, exit);
+    let mut compared = 0;
+    for x in 0..maze.dim.w {
+        for y in 0..maze.dim.h {
+            let p = Pos::new(x, y);
+            if !maze.is_room(p) || maze.is_locked(p) || p == exit || Pos::sides(p, exit) {
+                continue;
+            }
+            // the path found by A* stops just before the goal
+            let astar = find_astar(&maze, p, exit).map(|path| path.len() + 1);
+            assert_eq!(map.distance(p), astar, "from {p:?}");
+            compared += 1;
+        }
+    }
+    assert!(compared > 100);
+}
@@ -4,7 +4,7 @@ use {
 };
 
 /// A position
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct Pos {
     pub x: usize,
     pub y: usize,
@@ -145,7 +145,7 @@ impl PartialOrd for ValuedPos {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum Dir {
     Up,
     Right,
@@ -3,6 +3,7 @@ use crate::*;
 /// A mapping between positions in a rect and some
 /// values, with a default value on positions you
 /// didn't explicitly set
+#[derive(Clone)]
 pub struct PosMap<T: Copy> {
     dim: Dim,
     values: Box<[T]>,
@@ -63,3 +64,14 @@ impl<T: Copy + PartialEq> PosMap<T> {
     }
 }
 pub type PosSet = PosMap<bool>;
+impl PosSet {
+    /// Return the positions in the set, row after row
+    pub fn positions(&self) -> Vec<Pos> {
+        self.values
+            .iter()
+            .enumerate()
+            .filter(|(_, &v)| v)
+            .map(|(idx, _)| Pos::new(idx % self.dim.w, idx / self.dim.w))
+            .collect()
+    }
+}
@@ -1,6 +1,7 @@
 use {
     crate::*,
     std::{
+        borrow::Cow,
         io::Write,
         thread,
         time::Duration,
@@ -26,6 +27,9 @@ use {
 pub struct Renderer<'s> {
     pub skin: &'s Skin,
     pub display: Display,
+    pub charset: Charset,
+    /// The keys of the player, to which the tutorial messages are adapted
+    pub keymap: Option<&'s Keymap>,
 }
 
 impl<'s> Renderer<'s> {
@@ -37,7 +41,28 @@ impl<'s> Renderer<'s> {
         &self,
         maze: &Maze,
     ) -> Layout {
-        Layout::compute(maze.dim, maze.player(), self.display)
+        Layout::compute(maze.dim, maze.player(), self.display, self.charset)
+    }
+
+    /// Return the room of the maze drawn at the given screen cell, if any.
+    ///
+    /// When the cell draws several positions, the first room is taken.
+    /// Rooms the player never saw are ignored.
+    pub fn room_at(
+        &self,
+        maze: &Maze,
+        x: u16,
+        y: u16,
+    ) -> Option<Pos> {
+        self.layout(maze)
+            .screen_to_maze(x, y)
+            .into_iter()
+            .find(|&pos| {
+                pos.x < maze.dim.w
+                    && pos.y < maze.dim.h
+                    && maze.is_room(pos)
+                    && maze.visible_nature(pos) != Nature::Unknown
+            })
     }
 
     fn write_game_header<W: Write>(
@@ -48,15 +73,37 @@ impl<'s> Renderer<'s> {
     ) -> anyhow::Result<()> {
         w.queue(cursor::MoveTo(0, layout.margin.h as u16))?;
         self.spaces(w, layout.margin.w)?;
-        w.queue(Print(&maze.name))?;
+        let mut title = format!("{} - seed {}", maze.name, maze.seed());
+        if let Some(remaining) = maze.remaining_time() {
+            title.push_str(&format!(" - {}s left", remaining.as_secs()));
+        }
+        w.queue(Print(&title))?;
+        if !self.charset.is_colored() {
+            let right = format!("{} keys {} lives", maze.held_keys(), maze.lives);
+            if layout.content.w > title.len() + right.len() + 1 {
+                self.spaces(w, layout.content.w - title.len() - right.len())?;
+            } else {
+                self.spaces(w, 1)?;
+            }
+            w.queue(Print(right))?;
+            w.queue(Clear(ClearType::UntilNewLine))?;
+            return Ok(());
+        }
         let lives = if maze.lives > 3 {
             format!(" {} ■", maze.lives)
         } else {
             " ■".repeat(maze.lives as usize)
         };
-        if layout.content.w > maze.name.len() + 6 {
-            self.spaces(w, layout.content.w - maze.name.len() - 6)?;
+        let keys = " ⚷".repeat(maze.held_keys());
+        let right_width = 6 + 2 * maze.held_keys();
+        if layout.content.w > title.len() + right_width {
+            self.spaces(w, layout.content.w - title.len() - right_width)?;
         }
+        w.queue(SetColors(Colors {
+            foreground: Some(self.skin.key),
+            background: None,
+        }))?;
+        w.queue(Print(keys))?;
         w.queue(SetColors(Colors {
             foreground: Some(self.skin.potion),
             background: None,
@@ -77,7 +124,10 @@ impl<'s> Renderer<'s> {
             (1 + layout.margin.h + layout.content.h) as u16,
         ))?;
         self.spaces(w, layout.margin.w)?;
-        w.queue(Print(maze.status()))?;
+        match self.keymap {
+            Some(keymap) => w.queue(Print(keymap.tutorial(maze.status())))?,
+            None => w.queue(Print(maze.status()))?,
+        };
         w.queue(Clear(ClearType::UntilNewLine))?;
         Ok(())
     }
@@ -99,12 +149,12 @@ impl<'s> Renderer<'s> {
                     .iter()
                     .any(|p| p.x == pos.x && p.y == pos.y + 1);
                 let bottom_nature = if bottom_is_jump {
-                    Nature::Monster
+                    Nature::Monster(MonsterKind::Hunter)
                 } else {
                     maze.visible_nature(Pos::new(pos.x, pos.y + 1))
                 };
                 Colors {
-                    foreground: self.skin.color(Nature::Monster),
+                    foreground: Some(self.skin.monster),
                     background: self.skin.color(bottom_nature),
                 }
             } else {
@@ -114,7 +164,7 @@ impl<'s> Renderer<'s> {
                         .iter()
                         .any(|p| p.x == pos.x && p.y == pos.y - 1);
                     if top_is_jump {
-                        Nature::Monster
+                        Nature::Monster(MonsterKind::Hunter)
                     } else {
                         maze.visible_nature(Pos::new(pos.x, pos.y - 1))
                     }
@@ -123,7 +173,7 @@ impl<'s> Renderer<'s> {
                 };
                 Colors {
                     foreground: self.skin.color(top_nature),
-                    background: self.skin.color(Nature::Monster),
+                    background: Some(self.skin.monster),
                 }
             };
             w.queue(cursor::MoveTo(x, y))?;
@@ -142,7 +192,7 @@ impl<'s> Renderer<'s> {
             // x and y are for the leftest one of the two cells
             let (x, y) = layout.maze_to_screen_double_size(pos);
             w.queue(cursor::MoveTo(x, y))?;
-            w.queue(SetForegroundColor(self.skin.real_color(Nature::Monster)))?;
+            w.queue(SetForegroundColor(self.skin.monster))?;
             w.queue(Print("██"))?;
         }
         Ok(())
@@ -178,7 +228,7 @@ impl<'s> Renderer<'s> {
             }
             Dir::Right => {
                 let av_left = av.min(8); // left cell of each pos
-                let av_right = if av <= 8 { 0 } else { av - 8 };
+                let av_right = av.saturating_sub(8);
                 draw_bicolor_horizontal(w, x, y, start_bg, fg, av_left)?;
                 draw_bicolor_horizontal(w, x + 1, y, start_bg, fg, av_right)?;
                 draw_bicolor_horizontal(w, x + 2, y, fg, dest_bg, av_left)?;
@@ -224,6 +274,10 @@ impl<'s> Renderer<'s> {
             // a move can only be animated in alternate mode
             return Ok(false);
         }
+        if self.charset.is_text() || layout.density == Density::Braille {
+            // text and braille characters have no intermediate drawing
+            return Ok(false);
+        }
         if self.skin.room.is_none() {
             // block character animation is not possible without a room color
             return Ok(false);
@@ -235,7 +289,7 @@ impl<'s> Renderer<'s> {
             let Event::Teleport(teleport) = event else {
                 continue;
             };
-            if layout.double_sizes {
+            if layout.density == Density::Double {
                 self.draw_teleport_double_size(w, &layout, teleport)?;
             } else {
                 self.draw_teleport_half_size(w, &layout, maze, teleport)?;
@@ -244,13 +298,17 @@ impl<'s> Renderer<'s> {
             w.queue(ResetColor)?;
             w.flush()?;
         }
-        if layout.double_sizes {
+        if layout.density == Density::Double {
             // moves are drawn step by step, but only in double size
             for av in 1..=16 {
                 for event in &events.events {
                     let Event::Move(pos_move) = event else {
                         continue;
                     };
+                    if !maze.is_in_sight(pos_move.start) {
+                        // the player doesn't see monsters moving in the dark
+                        continue;
+                    }
                     self.draw_pos_move_step_double_size(w, &layout, *pos_move, av)?;
                 }
                 w.queue(ResetColor)?;
@@ -302,6 +360,74 @@ impl<'s> Renderer<'s> {
         Ok(())
     }
 
+    // the rendering with one character per game pos, for the text charsets
+    fn write_maze_single_size<W: Write>(
+        &self,
+        w: &mut W,
+        layout: &Layout,
+        maze: &Maze,
+    ) -> anyhow::Result<()> {
+        for l in 0..layout.content.h {
+            if self.is_alternate() {
+                w.queue(cursor::MoveTo(0, (l + 1 + layout.margin.h) as u16))?;
+            }
+            self.spaces(w, layout.margin.w)?;
+            for i in 0..layout.content.w {
+                let pos = Pos::new(i + layout.trim.w, l + layout.trim.h);
+                let c = self.charset.cell_char(maze, pos);
+                let color = if self.charset.is_colored() {
+                    self.skin.color(maze.visible_nature(pos))
+                } else {
+                    None
+                };
+                if let Some(color) = color {
+                    w.queue(SetForegroundColor(color))?;
+                    w.queue(Print(c))?;
+                    w.queue(ResetColor)?;
+                } else {
+                    w.queue(Print(c))?;
+                }
+            }
+            if self.is_alternate() {
+                w.queue(Clear(ClearType::UntilNewLine))?;
+            } else {
+                writeln!(w)?;
+            }
+        }
+        Ok(())
+    }
+
+    // the rendering of huge mazes, with 2x4 game positions per braille character
+    fn write_maze_braille<W: Write>(
+        &self,
+        w: &mut W,
+        layout: &Layout,
+        maze: &Maze,
+    ) -> anyhow::Result<()> {
+        for l in 0..layout.content.h {
+            if self.is_alternate() {
+                w.queue(cursor::MoveTo(0, (l + 1 + layout.margin.h) as u16))?;
+            }
+            self.spaces(w, layout.margin.w)?;
+            for i in 0..layout.content.w {
+                let top_left = Pos::new(2 * i + layout.trim.w, 4 * l + layout.trim.h);
+                if let Some((c, nature)) = braille_cell(maze, top_left) {
+                    w.queue(SetForegroundColor(self.skin.real_color(nature)))?;
+                    w.queue(Print(c))?;
+                    w.queue(ResetColor)?;
+                } else {
+                    w.queue(Print(' '))?;
+                }
+            }
+            if self.is_alternate() {
+                w.queue(Clear(ClearType::UntilNewLine))?;
+            } else {
+                writeln!(w)?;
+            }
+        }
+        Ok(())
+    }
+
     fn write_maze_half_size<W: Write>(
         &self,
         w: &mut W,
@@ -362,6 +488,54 @@ impl<'s> Renderer<'s> {
         Ok(())
     }
 
+    /// Render the list of the active key bindings, in place of the maze
+    pub fn write_help<W: Write>(
+        &self,
+        w: &mut W,
+        keymap: &Keymap,
+    ) -> anyhow::Result<()> {
+        let lines: Vec<(String, Cow<str>)> = COMMANDS
+            .iter()
+            .map(|&command| {
+                let keys: Vec<String> = keymap
+                    .keys(command)
+                    .iter()
+                    .map(|key| key.to_string())
+                    .collect();
+                (keys.join(", "), command.description())
+            })
+            .collect();
+        let keys_width = lines.iter().map(|(keys, _)| keys.len()).max().unwrap_or(0);
+        w.queue(cursor::MoveTo(0, 0))?;
+        w.queue(Clear(ClearType::All))?;
+        w.queue(cursor::MoveTo(2, 1))?;
+        w.queue(Print("Keys"))?;
+        for (i, (keys, description)) in lines.iter().enumerate() {
+            w.queue(cursor::MoveTo(2, 3 + i as u16))?;
+            w.queue(Print(format!("{keys:>keys_width$}  {description}")))?;
+        }
+        w.queue(cursor::MoveTo(2, 4 + lines.len() as u16))?;
+        w.queue(Print(
+            "ctrl-c always quits. Hit any key to go back to the game",
+        ))?;
+        Ok(())
+    }
+
+    /// Render a few lines of text, in place of the maze
+    pub fn write_message<W: Write>(
+        &self,
+        w: &mut W,
+        lines: &[&str],
+    ) -> anyhow::Result<()> {
+        w.queue(cursor::MoveTo(0, 0))?;
+        w.queue(Clear(ClearType::All))?;
+        for (i, line) in lines.iter().enumerate() {
+            w.queue(cursor::MoveTo(2, 1 + i as u16))?;
+            w.queue(Print(line))?;
+        }
+        Ok(())
+    }
+
     /// Render the maze (with title and lives count) for the TUI,
     /// assuming a buffered writer in an alternate
     pub fn write<W: Write>(
@@ -381,10 +555,11 @@ impl<'s> Renderer<'s> {
         if self.is_alternate() {
             self.write_game_header(w, &layout, maze)?;
         }
-        if layout.double_sizes {
-            self.write_maze_double_size(w, &layout, maze)?;
-        } else {
-            self.write_maze_half_size(w, &layout, maze)?;
+        match layout.density {
+            Density::Double => self.write_maze_double_size(w, &layout, maze)?,
+            Density::Single => self.write_maze_single_size(w, &layout, maze)?,
+            Density::Half => self.write_maze_half_size(w, &layout, maze)?,
+            Density::Braille => self.write_maze_braille(w, &layout, maze)?,
         }
         if self.is_alternate() {
             self.write_game_status(w, &layout, maze)?;
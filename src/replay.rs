@@ -0,0 +1,149 @@
+use {
+    crate::*,
+    std::{
+        fs,
+        path::{
+            Path,
+            PathBuf,
+        },
+        time::{
+            SystemTime,
+            UNIX_EPOCH,
+        },
+    },
+};
+
+/// How many replays are kept for a user, the oldest ones being removed
+pub const REPLAYS_PER_USER: usize = 100;
+
+/// The record of a game: as the maze and the game events only depend
+/// on the seed and on the player's actions, they're enough to replay it.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Replay {
+    pub user: String,
+    pub level: usize,
+    pub seed: u64,
+    /// The clock of a timed game
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clock: Option<Clock>,
+    pub actions: Vec<Action>,
+}
+
+impl Replay {
+    pub fn new(
+        user: &str,
+        level: usize,
+        seed: u64,
+    ) -> Self {
+        Self {
+            user: user.to_string(),
+            level,
+            seed,
+            clock: None,
+            actions: Vec::new(),
+        }
+    }
+    /// Build the maze of the game, as it was before the first action
+    pub fn maze(&self) -> Maze {
+        let mut specs = Specs::for_level(self.level);
+        specs.seed = Some(self.seed);
+        let mut maze = Maze::from(specs);
+        maze.set_clock(self.clock);
+        maze
+    }
+    /// Play again all the actions, without any display, and return
+    /// the maze as it was at the end of the game
+    pub fn final_maze(&self) -> Maze {
+        let mut maze = self.maze();
+        let mut events = EventList::default();
+        for action in &self.actions {
+            action.apply(&mut maze, &mut events);
+            events.clear();
+        }
+        maze
+    }
+    /// Save the replay in the replays directory of the data dir,
+    /// and return the path of the file
+    pub fn save(&self) -> anyhow::Result<PathBuf> {
+        let dir = data_dir()?.join("replays");
+        fs::create_dir_all(&dir)?;
+        let time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let user = file_safe(&self.user);
+        let path = dir.join(format!("{user}-level-{}-{time}.json", self.level));
+        fs::write(&path, serde_json::to_string(self)?)?;
+        info!("replay saved in {path:?}");
+        remove_old_replays(&dir, &user)?;
+        Ok(path)
+    }
+    pub fn from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+/// Remove the oldest replays of a user, so that at most
+/// [REPLAYS_PER_USER] are kept in the directory
+fn remove_old_replays(
+    dir: &Path,
+    user: &str,
+) -> anyhow::Result<()> {
+    let prefix = format!("{user}-level-");
+    let mut replays = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with(&prefix) {
+            replays.push((entry.metadata()?.modified()?, entry.path()));
+        }
+    }
+    if replays.len() > REPLAYS_PER_USER {
+        replays.sort();
+        for (_, path) in &replays[..replays.len() - REPLAYS_PER_USER] {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Return the given string with only the chars which can't be a problem
+/// in a file name
+pub(crate) fn file_safe(s: &str) -> String {
+    s.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[test]
+fn replay_is_exact() {
+    let mut replay = Replay::new("test", 27, 1234);
+    let mut maze = replay.maze();
+    let mut events = EventList::default();
+    while !(maze.is_won() || maze.is_lost()) && maze.turn() < 1000 {
+        Action::Auto.apply(&mut maze, &mut events);
+        replay.actions.push(Action::Auto);
+    }
+    let replayed = replay.final_maze();
+    assert_eq!(MazeData::from(&replayed), MazeData::from(&maze));
+    assert_eq!(replayed.player(), maze.player());
+    assert_eq!(replayed.lives_lost(), maze.lives_lost());
+}
+
+#[test]
+fn old_replays_are_removed() {
+    let dir = std::env::temp_dir().join(format!("mazter-replays-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let other = dir.join("someone_else-level-1-1.json");
+    fs::write(&other, "{}").unwrap();
+    for time in 0..REPLAYS_PER_USER + 5 {
+        fs::write(dir.join(format!("tester-level-1-{time:04}.json")), "{}").unwrap();
+        remove_old_replays(&dir, "tester").unwrap();
+    }
+    let count = fs::read_dir(&dir).unwrap().count();
+    assert_eq!(count, REPLAYS_PER_USER + 1);
+    assert!(other.exists());
+    assert!(!dir.join("tester-level-1-0000.json").exists());
+    assert!(
+        dir.join(format!("tester-level-1-{:04}.json", REPLAYS_PER_USER + 4))
+            .exists()
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -3,27 +3,69 @@ use {
     crokey::*,
     std::{
         io::Write,
-        time::Duration,
+        time::{
+            Duration,
+            Instant,
+        },
     },
     termimad::{
         EventSource,
         EventSourceOptions,
         Ticker,
         crossbeam::channel::select,
-        crossterm::event::Event,
+        crossterm::event::{
+            Event,
+            MouseButton,
+            MouseEventKind,
+        },
     },
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Tick {
     PlayerMoveAuto,
+    /// A step of the walk to a clicked room
+    WalkStep,
+    /// The time to hide the way shown by a hint
+    HintEnd,
+    /// A move of the world, in timed games
+    WorldMove,
+    ReplayStep,
     Continue,
 }
 
+/// Ask the user whether to resume the game they saved, and wait for
+/// their answer
+fn ask_resume<W: Write>(
+    w: &mut W,
+    renderer: &Renderer,
+    event_source: &EventSource,
+    saved: &SavedGame,
+) -> anyhow::Result<bool> {
+    let question = format!(
+        "You saved a game of level {}. Resume it? (y/n)",
+        saved.replay.level
+    );
+    renderer.write_message(w, &[&question, "If you don't, it will be dropped."])?;
+    w.flush()?;
+    loop {
+        let event = event_source.receiver().recv()?.event;
+        event_source.unblock(false);
+        if let Event::Key(key_event) = event {
+            match key_event.into() {
+                key!(y) | key!(enter) => return Ok(true),
+                key!(n) | key!(esc) => return Ok(false),
+                _ => {}
+            }
+        }
+    }
+}
+
 /// Run the game, assuming the terminal is already in alternate mode
 pub fn run<W: Write>(
     w: &mut W,
     skin: &Skin,
+    keymap: &Keymap,
     args: &Args,
 ) -> anyhow::Result<()> {
     let dim = Dim::terminal()?;
@@ -31,6 +73,8 @@ pub fn run<W: Write>(
     let mut renderer = Renderer {
         display: Display::Alternate(dim),
         skin,
+        charset: args.charset,
+        keymap: Some(keymap),
     };
     let user = if args.screen_saver {
         "screen-saver"
@@ -41,8 +85,25 @@ pub fn run<W: Write>(
         }
         user
     };
+    let mut ticker = Ticker::new();
+    let event_source = EventSource::with_options(EventSourceOptions {
+        combine_keys: false,
+        ..Default::default()
+    })?;
+    let user_events = event_source.receiver();
+
     let mut levels_won = 0;
-    let mut level = if let Some(level) = args.level {
+    // the trail stays shown or hidden from a game to the next
+    let mut show_trail = false;
+    // whether a walk step is scheduled, maybe for a walk already stopped,
+    // as there must never be two of them
+    let mut walk_tick_pending = false;
+    // the date is kept for the whole session, even when it goes past midnight
+    let daily_date = Specs::daily_date();
+    let mut level = if args.daily {
+        // the daily challenge is open to everyone
+        Specs::daily_level(daily_date)
+    } else if let Some(level) = args.level {
         if Database::can_play(user, level)? {
             level
         } else {
@@ -59,18 +120,66 @@ pub fn run<W: Write>(
         // normal users
         Database::first_not_won(user)?
     };
-
-    let mut ticker = Ticker::new();
-    let event_source = EventSource::with_options(EventSourceOptions {
-        combine_keys: false,
-        ..Default::default()
-    })?;
-    let user_events = event_source.receiver();
+    // when the player doesn't ask for a level, they may resume their saved game
+    let mut resumed = None;
+    if args.level.is_none() && args.load.is_none() && !args.daily && !args.screen_saver {
+        if let Some(saved) = SavedGame::load(user)? {
+            if ask_resume(w, &renderer, &event_source, &saved)? {
+                level = saved.replay.level;
+                resumed = Some(saved);
+            }
+            SavedGame::delete(user)?;
+        }
+    }
+    // a lost game the player rewound with an undo or a restart, with its start time
+    let mut rewound: Option<(History, Maze, Instant)> = None;
 
     loop {
-        let specs = Specs::for_level(level);
-        debug!("maze specs: {:#?}", &specs);
-        let mut maze: Maze = time!(specs.into());
+        let (mut history, mut maze, game_start) = if let Some(rewound) = rewound.take() {
+            rewound
+        } else {
+            let mut initial_maze: Maze = if let Some(saved) = &resumed {
+                saved.replay.maze()
+            } else if let Some(path) = &args.load {
+                load_maze(path)?
+            } else if args.daily {
+                let specs = Specs::for_daily(daily_date);
+                debug!("maze specs: {:#?}", &specs);
+                time!(specs.into())
+            } else {
+                let mut specs = Specs::for_level(level);
+                specs.seed = args.seed;
+                debug!("maze specs: {:#?}", &specs);
+                time!(specs.into())
+            };
+            if let (Some(period), None) = (args.timed, &resumed) {
+                let clock = Clock::new(&initial_maze, period);
+                initial_maze.set_clock(Some(clock));
+            }
+            let game_start = Instant::now();
+            match resumed.take() {
+                Some(saved) => {
+                    let game_start = game_start
+                        .checked_sub(Duration::from_millis(saved.millis))
+                        .unwrap_or(game_start);
+                    let (history, maze) = saved.restore();
+                    (history, maze, game_start)
+                }
+                None => (History::new(&initial_maze), initial_maze, game_start),
+            }
+        };
+        let seed = maze.seed();
+        // loaded mazes aren't levels and the screen saver's games aren't worth it
+        let replay = if args.load.is_none() && !args.screen_saver {
+            let mut replay = Replay::new(user, level, seed);
+            replay.clock = maze.clock();
+            Some(replay)
+        } else {
+            None
+        };
+        let mut clock_beam = maze
+            .clock()
+            .map(|clock| ticker.tick_infinitely(Tick::WorldMove, clock.period()));
         let mut screen_saver_beam = if args.screen_saver {
             // requesting periodic automatic player moves
             Some(ticker.tick_infinitely(Tick::PlayerMoveAuto, Duration::from_millis(140)))
@@ -78,24 +187,118 @@ pub fn run<W: Write>(
             None
         };
         let mut events = EventList::default();
+        // the rooms still to walk through, in reverse order, after a click
+        let mut walk: Vec<Pos> = Vec::new();
+        let mut help = false;
         while !(maze.is_won() || maze.is_lost()) {
-            renderer.write(w, &maze)?;
+            maze.show_trail = show_trail;
+            if help {
+                renderer.write_help(w, keymap)?;
+            } else {
+                renderer.write(w, &maze)?;
+            }
             w.flush()?;
             select! {
                 recv(user_events) -> user_event => {
                     match user_event?.event {
                         Event::Key(key_event) => match key_event.into() {
-                            key!(q) | key!(ctrl-c) | key!(ctrl-q) => {
+                            key!(ctrl-c) | key!(ctrl-q) => {
                                 return Ok(());
                             }
-                            key!(up) => maze.try_move(Dir::Up, &mut events),
-                            key!(right) => maze.try_move(Dir::Right, &mut events),
-                            key!(down) => maze.try_move(Dir::Down, &mut events),
-                            key!(left) => maze.try_move(Dir::Left, &mut events),
-                            key!(w) => maze.end_player_turn(&mut events),
-                            key!(a) => maze.give_up(),
-                            _ => {}
+                            _ if help => {
+                                // any key closes the help
+                                help = false;
+                            }
+                            key => {
+                                if !walk.is_empty() {
+                                    // a key stops the walk
+                                    walk.clear();
+                                    maze.clear_highlight();
+                                }
+                                match keymap.command(key) {
+                                    Some(Command::Quit) => {
+                                        return Ok(());
+                                    }
+                                    Some(Command::Help) => {
+                                        help = true;
+                                    }
+                                    Some(Command::Trail) => {
+                                        show_trail = !show_trail;
+                                    }
+                                    Some(Command::Undo) => {
+                                        if let Some(undone) = history.undo() {
+                                            maze = undone;
+                                        } else if history.undos_left() == 0 {
+                                            maze.default_status = "No undo left in this game";
+                                        }
+                                    }
+                                    Some(Command::Restart) => {
+                                        if let Some(restarted) = history.restart() {
+                                            maze = restarted;
+                                        } else if history.undos_left() == 0 {
+                                            maze.default_status = "No undo left in this game";
+                                        }
+                                    }
+                                    Some(Command::SaveAndQuit) => {
+                                        // the daily challenge must be played in one go
+                                        if let Some(replay) = replay.as_ref().filter(|_| !args.daily) {
+                                            let mut replay = replay.clone();
+                                            replay.actions = history.actions().to_vec();
+                                            SavedGame {
+                                                replay,
+                                                undos: history.undos_used(),
+                                                hints: history.hints_used(),
+                                                millis: game_start.elapsed().as_millis() as u64,
+                                                fingerprint: SavedGame::fingerprint(&maze),
+                                            }
+                                            .save()?;
+                                            return Ok(());
+                                        }
+                                        maze.default_status = "Only the games of levels can be saved";
+                                    }
+                                    Some(Command::Hint) => {
+                                        history.apply(Action::Hint, &mut maze, &mut events);
+                                        if maze.hints_used() >= HINTS_PER_LEVEL {
+                                            maze.default_status = "No hint left in this level";
+                                        }
+                                        ticker.tick_once(Tick::HintEnd, Duration::from_secs(2));
+                                    }
+                                    Some(command) => {
+                                        if let Some(action) = command.action() {
+                                            history.apply(action, &mut maze, &mut events);
+                                        }
+                                    }
+                                    None => {}
+                                }
+                            }
                         },
+                        Event::Mouse(mouse_event) if !args.screen_saver && !help => {
+                            let target = renderer.room_at(&maze, mouse_event.column, mouse_event.row);
+                            let path = target.and_then(|pos| maze.player_path_to(pos));
+                            match mouse_event.kind {
+                                MouseEventKind::Down(MouseButton::Left) => {
+                                    if let Some(path) = path {
+                                        if !walk_tick_pending {
+                                            ticker.tick_once(Tick::WalkStep, Duration::from_millis(0));
+                                            walk_tick_pending = true;
+                                        }
+                                        maze.set_highlights(&path);
+                                        walk = path;
+                                        walk.reverse();
+                                    }
+                                }
+                                MouseEventKind::Moved if walk.is_empty() => {
+                                    // preview of the way to the hovered room
+                                    match path {
+                                        Some(path) => maze.set_highlights(&path),
+                                        None => {
+                                            maze.clear_highlight();
+                                        }
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
                         Event::Resize(w, h) => {
                             renderer.display = Display::Alternate(Dim::new(w as usize, h as usize));
                         }
@@ -104,8 +307,36 @@ pub fn run<W: Write>(
                     event_source.unblock(false);
                 }
                 recv(ticker.tick_receiver) -> tick => {
-                    if tick? == Tick::PlayerMoveAuto {
-                        maze.move_player_auto(&mut events);
+                    match tick? {
+                        Tick::PlayerMoveAuto => {
+                            history.apply(Action::Auto, &mut maze, &mut events);
+                        }
+                        Tick::WalkStep => {
+                            walk_tick_pending = false;
+                            let step = walk.pop().and_then(|dest| {
+                                maze.player().and_then(|player| player.step_dir_to(dest))
+                            });
+                            if let Some(dir) = step {
+                                history.apply(Action::Move(dir), &mut maze, &mut events);
+                                let rest: Vec<Pos> = walk.iter().rev().copied().collect();
+                                maze.set_highlights(&rest);
+                            } else {
+                                // the walk is over, or the player was sent elsewhere
+                                walk.clear();
+                                maze.clear_highlight();
+                            }
+                            if !walk.is_empty() {
+                                ticker.tick_once(Tick::WalkStep, Duration::from_millis(60));
+                                walk_tick_pending = true;
+                            }
+                        }
+                        Tick::WorldMove if maze.clock().is_some() && !help => {
+                            history.apply(Action::Tick, &mut maze, &mut events);
+                        }
+                        Tick::HintEnd if walk.is_empty() => {
+                            maze.clear_highlight();
+                        }
+                        _ => {}
                     }
                 }
             }
@@ -114,24 +345,53 @@ pub fn run<W: Write>(
                 events.clear();
             }
         }
+        // the previews and walks of the mouse end with the game
+        maze.clear_highlight();
         if let Some(beam) = screen_saver_beam.take() {
             ticker.stop_beam(beam);
         }
+        if let Some(beam) = clock_beam.take() {
+            ticker.stop_beam(beam);
+        }
+        let mut replay_path = None;
+        if let Some(mut replay) = replay {
+            // undone turns aren't part of the recorded game
+            replay.actions = history.actions().to_vec();
+            replay_path = Some(replay.save()?);
+        }
         if maze.is_won() {
-            levels_won += 1;
-            if let Some(levels) = args.levels {
-                if levels_won >= levels {
-                    return Ok(());
-                }
+            if args.load.is_some() {
+                // a loaded maze isn't a level, there's nothing to record
+                return Ok(());
             }
-            let next_not_won_level = Database::advance(Achievement::new(user, level))?;
-            level = if args.screen_saver {
-                level + 1
+            if args.daily {
+                // the daily challenge isn't a level, its wins have their own leaderboard
+                let score = Score::new(&maze, game_start.elapsed());
+                Database::add_daily(user, daily_date, score)?;
             } else {
-                next_not_won_level
-            };
+                levels_won += 1;
+                if let Some(levels) = args.levels {
+                    if levels_won >= levels {
+                        return Ok(());
+                    }
+                }
+                let achievement = Achievement::new(user, level);
+                let score = Score::new(&maze, game_start.elapsed());
+                maze.personal_best = Database::is_personal_best(achievement, &score)?;
+                let next_not_won_level = Database::advance(achievement, Some(score))?;
+                level = if args.screen_saver {
+                    level + 1
+                } else {
+                    next_not_won_level
+                };
+            }
         } else {
-            maze.highlight_path_to_exit(maze.start());
+            maze.rewindable = !args.screen_saver && history.undos_left() > 0;
+            // the way isn't shown while the game can be rewound
+            if !maze.rewindable {
+                maze.reveal();
+                maze.highlight_path_to_exit(maze.start());
+            }
         }
         // waiting while the user is displayed that he won or lost
         renderer.write(w, &maze)?;
@@ -145,8 +405,15 @@ pub fn run<W: Write>(
         loop {
             select! {
                 recv(ticker.tick_receiver) -> tick => {
-                    if tick? == Tick::Continue {
-                        break;
+                    match tick? {
+                        Tick::Continue => {
+                            break;
+                        }
+                        Tick::WalkStep => {
+                            // the step of a walk ended with the game
+                            walk_tick_pending = false;
+                        }
+                        _ => {}
                     }
                 }
                 recv(user_events) -> user_event => {
@@ -155,9 +422,31 @@ pub fn run<W: Write>(
                             key!(ctrl - c) | key!(ctrl - q) => {
                                 return Ok(());
                             }
+                            key if maze.rewindable => {
+                                // a lost game may be rewound instead of replaced
+                                let rewound_maze = match keymap.command(key) {
+                                    Some(Command::Undo) => history.undo(),
+                                    Some(Command::Restart) => history.restart(),
+                                    _ => None,
+                                };
+                                if let Some(rewound_maze) = rewound_maze {
+                                    if let Some(path) = replay_path.take() {
+                                        // the game goes on, it will be recorded when finished
+                                        std::fs::remove_file(path)?;
+                                    }
+                                    rewound = Some((history, rewound_maze, game_start));
+                                    event_source.unblock(false);
+                                    break;
+                                }
+                            }
                             _ => {
                             }
                         }
+                        Event::Mouse(mouse_event) if mouse_event.kind == MouseEventKind::Moved => {
+                            // moving the mouse isn't hitting a key
+                            event_source.unblock(false);
+                            continue;
+                        }
                         Event::Resize(w, h) => {
                             renderer.display = Display::Alternate(Dim::new(w as usize, h as usize));
                         }
@@ -168,5 +457,86 @@ pub fn run<W: Write>(
                 }
             }
         }
+        if args.daily && maze.is_won() {
+            // there's one daily challenge a day
+            return Ok(());
+        }
+    }
+}
+
+/// Play back a recorded game, assuming the terminal is already in alternate mode
+pub fn run_replay<W: Write>(
+    w: &mut W,
+    skin: &Skin,
+    charset: Charset,
+    replay: &Replay,
+) -> anyhow::Result<()> {
+    let dim = Dim::terminal()?;
+    let mut renderer = Renderer {
+        display: Display::Alternate(dim),
+        skin,
+        charset,
+        keymap: None,
+    };
+    let mut maze = replay.maze();
+    maze.name = format!("Replay of {} - {}", replay.user, maze.name);
+    maze.default_status = "Hit q to quit";
+    let mut ticker = Ticker::new();
+    let event_source = EventSource::with_options(EventSourceOptions {
+        combine_keys: false,
+        ..Default::default()
+    })?;
+    let user_events = event_source.receiver();
+    let beam = ticker.tick_infinitely(Tick::ReplayStep, Duration::from_millis(140));
+    let mut actions = replay.actions.iter();
+    let mut events = EventList::default();
+    loop {
+        renderer.write(w, &maze)?;
+        w.flush()?;
+        select! {
+            recv(user_events) -> user_event => {
+                match user_event?.event {
+                    Event::Key(key_event) => match key_event.into() {
+                        key!(q) | key!(ctrl-c) | key!(ctrl-q) => {
+                            return Ok(());
+                        }
+                        _ => {}
+                    },
+                    Event::Resize(w, h) => {
+                        renderer.display = Display::Alternate(Dim::new(w as usize, h as usize));
+                    }
+                    _ => {}
+                }
+                event_source.unblock(false);
+            }
+            recv(ticker.tick_receiver) -> tick => {
+                if tick? == Tick::ReplayStep {
+                    let Some(action) = actions.next() else {
+                        break;
+                    };
+                    // the way shown by a hint is hidden at the next step
+                    maze.clear_highlight();
+                    action.apply(&mut maze, &mut events);
+                }
+            }
+        }
+        if !events.is_empty() {
+            renderer.animate_events(w, &maze, &events)?;
+            events.clear();
+        }
+    }
+    ticker.stop_beam(beam);
+    if !maze.is_won() {
+        maze.reveal();
+        maze.highlight_path_to_exit(maze.start());
+    }
+    maze.default_status = "End of the replay. Hit any key to quit";
+    renderer.write(w, &maze)?;
+    w.flush()?;
+    loop {
+        if let Event::Key(_) = user_events.recv()?.event {
+            return Ok(());
+        }
+        event_source.unblock(false);
     }
 }
@@ -0,0 +1,126 @@
+use {
+    crate::*,
+    fnv::FnvHasher,
+    std::{
+        fs,
+        hash::{
+            Hash,
+            Hasher,
+        },
+        path::PathBuf,
+    },
+};
+
+/// A game interrupted by the player, to be resumed at next launch.
+///
+/// As turns are deterministic, the actions of the game are enough to
+/// rebuild the whole state of the maze, as long as the generation and
+/// the rules didn't change, which the fingerprint checks.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SavedGame {
+    pub replay: Replay,
+    /// The undos already used in the game
+    pub undos: usize,
+    /// The hints already used in the game, undone ones included
+    #[serde(default)]
+    pub hints: usize,
+    /// The time already spent in the game
+    pub millis: u64,
+    /// The fingerprint of the maze as it was when saved
+    pub fingerprint: u64,
+}
+
+impl SavedGame {
+    /// Compute a hash of the state of a game: the maze, its monsters,
+    /// and the player
+    pub fn fingerprint(maze: &Maze) -> u64 {
+        let mut hasher = FnvHasher::default();
+        serde_json::to_string(&MazeData::from(maze))
+            .unwrap_or_default()
+            .hash(&mut hasher);
+        maze.turn().hash(&mut hasher);
+        maze.player().map(|p| (p.x, p.y)).hash(&mut hasher);
+        maze.lives.hash(&mut hasher);
+        hasher.finish()
+    }
+    /// Tell whether the game, replayed, is as it was when saved, which
+    /// isn't the case when mazter was upgraded with another generation
+    /// or other rules
+    pub fn is_valid(&self) -> bool {
+        let (_, maze) = self.restore();
+        Self::fingerprint(&maze) == self.fingerprint
+    }
+    /// The path of the game saved by a user, there's at most one
+    fn path(user: &str) -> anyhow::Result<PathBuf> {
+        Ok(data_dir()?.join(format!("saved-game-{}.json", file_safe(user))))
+    }
+    pub fn save(&self) -> anyhow::Result<PathBuf> {
+        let path = Self::path(&self.replay.user)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, serde_json::to_string(self)?)?;
+        info!("game saved in {path:?}");
+        Ok(path)
+    }
+    /// Return the game the user saved, if any and if it can still be
+    /// resumed, a stale one being dropped
+    pub fn load(user: &str) -> anyhow::Result<Option<Self>> {
+        let path = Self::path(user)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)?;
+        match serde_json::from_str::<Self>(&content) {
+            Ok(saved) if saved.is_valid() => Ok(Some(saved)),
+            _ => {
+                info!("dropping the stale saved game {path:?}");
+                fs::remove_file(path)?;
+                Ok(None)
+            }
+        }
+    }
+    pub fn delete(user: &str) -> anyhow::Result<()> {
+        let path = Self::path(user)?;
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+    /// Rebuild the history of the game, and the maze as it was when saved
+    pub fn restore(&self) -> (History, Maze) {
+        History::restore(
+            self.replay.maze(),
+            self.replay.actions.clone(),
+            self.undos,
+            self.hints,
+        )
+    }
+}
+
+#[test]
+fn saved_games_are_checked() {
+    let mut specs = Specs::for_level(15);
+    specs.seed = Some(7);
+    let mut maze = Maze::from(specs);
+    let mut history = History::new(&maze);
+    let mut events = EventList::default();
+    for _ in 0..10 {
+        history.apply(Action::Auto, &mut maze, &mut events);
+        events.clear();
+    }
+    let mut replay = Replay::new("tester", 15, 7);
+    replay.actions = history.actions().to_vec();
+    let mut saved = SavedGame {
+        replay,
+        undos: history.undos_used(),
+        hints: history.hints_used(),
+        millis: 1000,
+        fingerprint: SavedGame::fingerprint(&maze),
+    };
+    assert!(saved.is_valid());
+    assert_eq!(saved.restore().1.player(), maze.player());
+    // as if the game had been saved with another version of mazter
+    saved.replay.actions.pop();
+    assert!(!saved.is_valid());
+}
@@ -0,0 +1,231 @@
+use {
+    crate::*,
+    anyhow::bail,
+    std::{
+        fs,
+        path::Path,
+    },
+};
+
+/// The outline of a maze.
+///
+/// A shape is turned into a mask, the set of positions where rooms
+/// may be dug.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Shape {
+    Rectangle,
+    Disk,
+    Ring,
+    Heart,
+    Diamond,
+    Cross,
+    /// Some text, written in big letters
+    Banner(String),
+    /// An ASCII-art drawing where `#` means inside, each char being
+    /// one cell wide and two cells high
+    Drawing(Vec<String>),
+}
+
+pub static SHAPE_NAMES: &[&str] = &["rectangle", "disk", "ring", "heart", "diamond", "cross"];
+
+/// Height in pixels of the banner font
+const GLYPH_HEIGHT: usize = 5;
+
+/// How many maze cells make a pixel of the banner font, in both directions
+const BANNER_SCALE: usize = 4;
+
+impl Shape {
+    /// Parse the shape given in launch arguments: either the name of
+    /// a built-in shape, `banner:` followed by some text, or the path
+    /// to a text file
+    pub fn from_arg(arg: &str) -> anyhow::Result<Self> {
+        let shape = match arg {
+            "rectangle" => Self::Rectangle,
+            "disk" => Self::Disk,
+            "ring" => Self::Ring,
+            "heart" => Self::Heart,
+            "diamond" => Self::Diamond,
+            "cross" => Self::Cross,
+            _ => {
+                if let Some(text) = arg.strip_prefix("banner:") {
+                    if text.trim().is_empty() {
+                        bail!("The banner text can't be empty");
+                    }
+                    Self::Banner(text.to_string())
+                } else if Path::new(arg).exists() {
+                    Self::from_file(arg)?
+                } else {
+                    bail!(
+                        "Shape must be one of {}, banner:<text>, or the path to a text file",
+                        SHAPE_NAMES.join(", "),
+                    );
+                }
+            }
+        };
+        Ok(shape)
+    }
+    /// Read a drawing from a text file, where `#` means inside
+    pub fn from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let lines: Vec<String> = content
+            .lines()
+            .map(|line| line.trim_end().to_string())
+            .collect();
+        if !lines.iter().any(|line| line.contains('#')) {
+            bail!("No '#' in the shape file");
+        }
+        Ok(Self::Drawing(lines))
+    }
+    /// Return the dimensions the maze must have for this shape,
+    /// if they're imposed by the shape
+    pub fn natural_dim(&self) -> Option<Dim> {
+        match self {
+            Self::Banner(text) => {
+                let w = banner_pixels(text).len();
+                Some(Dim::new(
+                    w * BANNER_SCALE + 2,
+                    (GLYPH_HEIGHT + 1) * BANNER_SCALE + 2,
+                ))
+            }
+            Self::Drawing(lines) => {
+                let w = lines
+                    .iter()
+                    .map(|line| line.chars().count())
+                    .max()
+                    .unwrap_or(0);
+                Some(Dim::new(w + 2, 2 * lines.len() + 2))
+            }
+            _ => None,
+        }
+    }
+    /// Return the mask of the shape: positions where rooms may be dug.
+    ///
+    /// The border of the maze is never part of the mask.
+    pub fn mask(
+        &self,
+        dim: Dim,
+    ) -> PosSet {
+        let mut mask = PosSet::new(dim, false);
+        if dim.w < 3 || dim.h < 3 {
+            return mask;
+        }
+        let center = Pos::new(dim.w / 2, dim.h / 2);
+        let r = dim.w.min(dim.h) / 2 + 1;
+        let sq_r = r * r;
+        // normalized coordinates, in [-1, 1] inside the border
+        let rx = (dim.w - 2) as f32 / 2.0;
+        let ry = (dim.h - 2) as f32 / 2.0;
+        let banner = match self {
+            Self::Banner(text) => banner_pixels(text),
+            _ => Vec::new(),
+        };
+        for x in 1..dim.w - 1 {
+            for y in 1..dim.h - 1 {
+                let p = Pos::new(x, y);
+                let u = (x as f32 + 0.5 - 1.0 - rx) / rx;
+                let v = (y as f32 + 0.5 - 1.0 - ry) / ry;
+                let inside = match self {
+                    Self::Rectangle => true,
+                    // small disks would be too cramped
+                    Self::Disk => r <= 11 || Pos::sq_euclidian_distance(p, center) <= sq_r,
+                    Self::Ring => {
+                        let sq_d = Pos::sq_euclidian_distance(p, center);
+                        sq_d <= sq_r && sq_d * 5 >= sq_r
+                    }
+                    Self::Heart => {
+                        let (x, y) = (1.15 * u, 0.12 - 1.12 * v);
+                        let a = x * x + y * y - 1.0;
+                        a * a * a - x * x * y * y * y <= 0.0
+                    }
+                    Self::Diamond => u.abs() + v.abs() <= 1.0,
+                    Self::Cross => u.abs() <= 0.34 || v.abs() <= 0.34,
+                    Self::Banner(_) => {
+                        let (px, py) = ((x - 1) / BANNER_SCALE, (y - 1) / BANNER_SCALE);
+                        banner
+                            .get(px)
+                            .map_or(false, |column| py <= GLYPH_HEIGHT && column[py])
+                    }
+                    Self::Drawing(lines) => {
+                        let c = lines
+                            .get((y - 1) / 2)
+                            .and_then(|line| line.chars().nth(x - 1));
+                        c == Some('#')
+                    }
+                };
+                if inside {
+                    mask.set(p, true);
+                }
+            }
+        }
+        mask
+    }
+}
+
+/// Return the columns of pixels of the banner, with an underline
+/// connecting all letters
+fn banner_pixels(text: &str) -> Vec<[bool; GLYPH_HEIGHT + 1]> {
+    let mut columns = Vec::new();
+    for (i, c) in text.trim().chars().enumerate() {
+        if i > 0 {
+            // spacing between letters
+            let mut column = [false; GLYPH_HEIGHT + 1];
+            column[GLYPH_HEIGHT] = true;
+            columns.push(column);
+        }
+        let rows = glyph(c);
+        for x in 0..5 {
+            let mut column = [false; GLYPH_HEIGHT + 1];
+            for (y, row) in rows.iter().enumerate() {
+                column[y] = row.as_bytes()[x] == b'#';
+            }
+            column[GLYPH_HEIGHT] = true;
+            columns.push(column);
+        }
+    }
+    columns
+}
+
+/// A 5x5 font whose glyphs are connected to the underline, with no
+/// detached part, so that the maze can cover them
+fn glyph(c: char) -> [&'static str; GLYPH_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        'A' => ["#####", "#...#", "#####", "#...#", "#...#"],
+        'B' => ["####.", "#..#.", "#####", "#...#", "#####"],
+        'C' => ["#####", "#....", "#....", "#....", "#####"],
+        'D' => ["####.", "#..##", "#...#", "#..##", "####."],
+        'E' => ["#####", "#....", "####.", "#....", "#####"],
+        'F' => ["#####", "#....", "####.", "#....", "#...."],
+        'G' => ["#####", "#....", "#.###", "#...#", "#####"],
+        'H' => ["#...#", "#...#", "#####", "#...#", "#...#"],
+        'I' => ["#####", "..#..", "..#..", "..#..", "#####"],
+        'J' => ["#####", "...#.", "...#.", "#..#.", "####."],
+        'K' => ["#..##", "#..#.", "####.", "#..#.", "#..##"],
+        'L' => ["#....", "#....", "#....", "#....", "#####"],
+        'M' => ["#####", "#.#.#", "#.#.#", "#...#", "#...#"],
+        'N' => ["###.#", "#.#.#", "#.#.#", "#.#.#", "#.###"],
+        'O' => ["#####", "#...#", "#...#", "#...#", "#####"],
+        'P' => ["#####", "#...#", "#####", "#....", "#...."],
+        'Q' => ["#####", "#...#", "#...#", "#..##", "#####"],
+        'R' => ["#####", "#...#", "#####", "#..#.", "#...#"],
+        'S' => ["#####", "#....", "#####", "....#", "#####"],
+        'T' => ["#####", "..#..", "..#..", "..#..", "..#.."],
+        'U' => ["#...#", "#...#", "#...#", "#...#", "#####"],
+        'V' => ["#...#", "#...#", "##.##", ".#.#.", ".###."],
+        'W' => ["#...#", "#...#", "#.#.#", "#.#.#", "#####"],
+        'X' => ["##.##", ".#.#.", ".###.", ".#.#.", "##.##"],
+        'Y' => ["#...#", "#...#", "#####", "..#..", "..#.."],
+        'Z' => ["#####", "...##", "..##.", ".##..", "#####"],
+        '0' => ["#####", "#..##", "#...#", "##..#", "#####"],
+        '1' => ["###..", "..#..", "..#..", "..#..", "#####"],
+        '2' => ["#####", "....#", "#####", "#....", "#####"],
+        '3' => ["#####", "....#", ".####", "....#", "#####"],
+        '4' => ["#..#.", "#..#.", "#####", "...#.", "...#."],
+        '5' => ["#####", "#....", "#####", "....#", "#####"],
+        '6' => ["#####", "#....", "#####", "#...#", "#####"],
+        '7' => ["#####", "....#", "...##", "...#.", "...#."],
+        '8' => ["#####", "#...#", "#####", "#...#", "#####"],
+        '9' => ["#####", "#...#", "#####", "....#", "#####"],
+        _ => [".....", ".....", ".....", ".....", "....."],
+    }
+}
@@ -0,0 +1,179 @@
+use {
+    crate::*,
+    std::{
+        io::Write,
+        ops::RangeInclusive,
+        str::FromStr,
+    },
+};
+
+/// Number of turns after which a simulated game is considered lost
+const MAX_TURNS: usize = 5_000;
+
+/// A range of levels, given as `12` or `10-20`
+#[derive(Debug, Clone)]
+pub struct LevelRange(pub RangeInclusive<usize>);
+
+impl FromStr for LevelRange {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |s: &str| {
+            s.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("invalid level: {s:?}"))
+        };
+        let range = match s.split_once('-') {
+            Some((start, end)) => parse(start)?..=parse(end)?,
+            None => {
+                let level = parse(s)?;
+                level..=level
+            }
+        };
+        if range.is_empty() {
+            return Err(format!("empty level range: {s:?}"));
+        }
+        Ok(Self(range))
+    }
+}
+
+/// What happened in a simulated game
+#[derive(Debug, Clone, Copy)]
+pub struct GameOutcome {
+    pub won: bool,
+    pub turns: usize,
+    pub lives_lost: usize,
+    pub potions_drunk: usize,
+}
+
+/// Play a maze with the bot until it's won or lost, without any terminal
+pub fn simulate_game(mut maze: Maze) -> GameOutcome {
+    let mut events = EventList::default();
+    while !(maze.is_won() || maze.is_lost()) && maze.turn() < MAX_TURNS {
+        Action::Auto.apply(&mut maze, &mut events);
+        events.clear();
+    }
+    GameOutcome {
+        won: maze.is_won(),
+        turns: maze.turn(),
+        lives_lost: maze.lives_lost(),
+        potions_drunk: maze.potions_drunk(),
+    }
+}
+
+/// Accumulated outcomes of the games played on a level
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LevelStats {
+    pub level: usize,
+    pub runs: usize,
+    pub wins: usize,
+    pub turns: usize,
+    pub lives_lost: usize,
+    pub potions_drunk: usize,
+}
+
+impl LevelStats {
+    pub fn add(
+        &mut self,
+        outcome: GameOutcome,
+    ) {
+        self.runs += 1;
+        if outcome.won {
+            self.wins += 1;
+        }
+        self.turns += outcome.turns;
+        self.lives_lost += outcome.lives_lost;
+        self.potions_drunk += outcome.potions_drunk;
+    }
+    pub fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.runs as f64
+    }
+    fn mean(
+        &self,
+        total: usize,
+    ) -> String {
+        format!("{:.1}", total as f64 / self.runs as f64)
+    }
+}
+
+/// Play the levels several times each with the bot, and return
+/// the statistics of each level
+pub fn simulate_levels(
+    levels: &LevelRange,
+    runs: usize,
+    seed: Option<u64>,
+) -> anyhow::Result<Vec<LevelStats>> {
+    if runs == 0 {
+        anyhow::bail!("There must be at least one run per level");
+    }
+    let mut all_stats = Vec::new();
+    for level in levels.0.clone() {
+        let mut stats = LevelStats {
+            level,
+            ..Default::default()
+        };
+        for run in 0..runs {
+            let mut specs = Specs::for_level(level);
+            // with a given seed, the whole simulation can be reproduced
+            specs.seed = Some(seed.map_or_else(random_seed, |seed| seed.wrapping_add(run as u64)));
+            stats.add(simulate_game(specs.into()));
+        }
+        debug!("{stats:?}");
+        all_stats.push(stats);
+    }
+    Ok(all_stats)
+}
+
+/// Write the averages of the level statistics as CSV
+pub fn write_stats_csv<W: Write>(
+    w: &mut W,
+    all_stats: &[LevelStats],
+) -> anyhow::Result<()> {
+    writeln!(w, "level,runs,win_rate,turns,lives_lost,potions_drunk")?;
+    for s in all_stats {
+        writeln!(
+            w,
+            "{},{},{:.3},{},{},{}",
+            s.level,
+            s.runs,
+            s.win_rate(),
+            s.mean(s.turns),
+            s.mean(s.lives_lost),
+            s.mean(s.potions_drunk),
+        )?;
+    }
+    Ok(())
+}
+
+/// Print the averages of the level statistics as a table
+#[cfg(feature = "tui")]
+pub fn print_stats_table(all_stats: &[LevelStats]) {
+    use termimad::{
+        MadSkin,
+        minimad::{
+            Alignment,
+            Col,
+            OwningTemplateExpander,
+            TableBuilder,
+        },
+    };
+    let mut expander = OwningTemplateExpander::new();
+    for s in all_stats {
+        expander
+            .sub("rows")
+            .set("level", s.level)
+            .set("runs", s.runs)
+            .set("win_rate", format!("{:.0}%", 100.0 * s.win_rate()))
+            .set("turns", s.mean(s.turns))
+            .set("lives_lost", s.mean(s.lives_lost))
+            .set("potions", s.mean(s.potions_drunk));
+    }
+    let mut tbl = TableBuilder::default();
+    tbl.col(Col::new("**Level**", "${level}").align_content(Alignment::Right));
+    tbl.col(Col::new("**Runs**", "${runs}").align_content(Alignment::Right));
+    tbl.col(Col::new("**Win rate**", "${win_rate}").align_content(Alignment::Right));
+    tbl.col(Col::new("**Turns**", "${turns}").align_content(Alignment::Right));
+    tbl.col(Col::new("**Lives lost**", "${lives_lost}").align_content(Alignment::Right));
+    tbl.col(Col::new("**Potions**", "${potions}").align_content(Alignment::Right));
+    let skin = MadSkin::default();
+    skin.print_owning_expander_md(&expander, &tbl);
+}
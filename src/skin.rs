@@ -1,6 +1,12 @@
 use {
-    crate::Nature,
-    termimad::crossterm::style::Color,
+    crate::{
+        MonsterKind,
+        Nature,
+    },
+    termimad::{
+        coolor,
+        crossterm::style::Color,
+    },
 };
 
 pub struct Skin {
@@ -8,19 +14,115 @@ pub struct Skin {
     pub player: Color,
     pub highlight: Color,
     pub monster: Color,
+    pub slow_monster: Color,
+    pub wanderer: Color,
+    pub guard: Color,
+    pub ambusher: Color,
     pub potion: Color,
+    pub door: Color,
+    pub key: Color,
     pub room: Option<Color>,
+    pub unknown: Color,
+    pub remembered: Color,
+    pub visited: Color,
 }
 impl Skin {
+    /// Build the default skin, with rooms of the terminal's background color
     pub fn build() -> Self {
-        let room = terminal_light::background_color().ok().map(|c| c.into());
+        Self::with_room(Self::terminal_background())
+    }
+    pub(crate) fn terminal_background() -> Option<Color> {
+        terminal_light::background_color().ok().map(|c| c.into())
+    }
+    /// Build the default skin, with the given color of rooms, `None` meaning
+    /// the terminal's background is left as is
+    pub fn with_room(room: Option<Color>) -> Self {
+        let unknown = Color::AnsiValue(237);
+        let remembered = Self::fog(room, unknown);
+        let player = Color::AnsiValue(214);
+        let visited = Self::trail(room, player);
         Self {
             wall: Color::AnsiValue(102),
-            player: Color::AnsiValue(214),
+            player,
             highlight: Color::AnsiValue(45),
             monster: Color::AnsiValue(196),
+            slow_monster: Color::AnsiValue(88),
+            wanderer: Color::AnsiValue(135),
+            guard: Color::AnsiValue(33),
+            ambusher: Color::AnsiValue(213),
             potion: Color::AnsiValue(35),
+            door: Color::AnsiValue(94),
+            key: Color::AnsiValue(226),
             room,
+            unknown,
+            remembered,
+            visited,
+        }
+    }
+    /// Return the color of the rooms the player went through, which are
+    /// slightly tinted with the player's color
+    pub(crate) fn trail(
+        room: Option<Color>,
+        player: Color,
+    ) -> Color {
+        match room {
+            Some(room) => coolor::Color::blend(room, 0.8, player, 0.2).into(),
+            None => Color::AnsiValue(58),
+        }
+    }
+    /// Return the color of remembered rooms, which look like rooms in a fog
+    pub(crate) fn fog(
+        room: Option<Color>,
+        unknown: Color,
+    ) -> Color {
+        match room {
+            Some(room) => coolor::Color::blend(room, 0.7, unknown, 0.3).into(),
+            None => Color::AnsiValue(234),
+        }
+    }
+    /// Change one of the colors, given the name of its field
+    pub fn set_color(
+        &mut self,
+        name: &str,
+        color: Option<Color>,
+    ) -> anyhow::Result<()> {
+        let field = match name {
+            "room" => {
+                self.room = color;
+                return Ok(());
+            }
+            "wall" => &mut self.wall,
+            "player" => &mut self.player,
+            "highlight" => &mut self.highlight,
+            "monster" => &mut self.monster,
+            "slow_monster" => &mut self.slow_monster,
+            "wanderer" => &mut self.wanderer,
+            "guard" => &mut self.guard,
+            "ambusher" => &mut self.ambusher,
+            "potion" => &mut self.potion,
+            "door" => &mut self.door,
+            "key" => &mut self.key,
+            "unknown" => &mut self.unknown,
+            "remembered" => &mut self.remembered,
+            "visited" => &mut self.visited,
+            _ => anyhow::bail!("unknown color {name:?}"),
+        };
+        let Some(color) = color else {
+            anyhow::bail!("{name:?} can't be \"none\", only the room can");
+        };
+        *field = color;
+        Ok(())
+    }
+    pub fn monster_color(
+        &self,
+        kind: MonsterKind,
+    ) -> Color {
+        match kind {
+            MonsterKind::Hunter => self.monster,
+            MonsterKind::Slow => self.slow_monster,
+            MonsterKind::Wanderer => self.wanderer,
+            MonsterKind::Guard => self.guard,
+            MonsterKind::Ambusher => self.ambusher,
         }
     }
     pub fn color(
@@ -29,10 +131,15 @@ impl Skin {
     ) -> Option<Color> {
         match nature {
             Nature::Wall => Some(self.wall),
-            Nature::Monster => Some(self.monster),
+            Nature::Monster(kind) => Some(self.monster_color(kind)),
             Nature::Player => Some(self.player),
             Nature::Potion => Some(self.potion),
+            Nature::Door => Some(self.door),
+            Nature::Key => Some(self.key),
             Nature::Highlight => Some(self.highlight),
+            Nature::Unknown => Some(self.unknown),
+            Nature::Remembered => Some(self.remembered),
+            Nature::Visited => Some(self.visited),
             Nature::Room | Nature::InvisibleWall => self.room,
         }
     }
@@ -42,10 +149,15 @@ impl Skin {
     ) -> Color {
         match nature {
             Nature::Wall => self.wall,
-            Nature::Monster => self.monster,
+            Nature::Monster(kind) => self.monster_color(kind),
             Nature::Player => self.player,
             Nature::Potion => self.potion,
+            Nature::Door => self.door,
+            Nature::Key => self.key,
             Nature::Highlight => self.highlight,
+            Nature::Unknown => self.unknown,
+            Nature::Remembered => self.remembered,
+            Nature::Visited => self.visited,
             Nature::Room | Nature::InvisibleWall => self.room.unwrap_or(Color::Black),
         }
     }
@@ -1,11 +1,25 @@
 use {
     crate::*,
+    chrono::{
+        Datelike,
+        NaiveDate,
+        Utc,
+    },
     rand::{
         Rng,
+        SeedableRng,
         thread_rng,
     },
+    rand_chacha::ChaCha8Rng,
 };
 
+// Tutorial messages of the first levels, written for the default keys and
+// adapted to the player's keymap when displayed.
+// They're part of the specs, so changing them would invalidate achievements.
+pub const MOVE_TUTORIAL: &str = "Use arrow keys to move and exit the maze";
+pub const GIVE_UP_TUTORIAL: &str = "You can abandon with key 'a'";
+pub const WAIT_TUTORIAL: &str = "Hit 'w' to wait";
+
 /// Definition of a maze to build
 #[derive(Debug, Clone, Hash)]
 pub struct Specs {
@@ -13,11 +27,21 @@ pub struct Specs {
     pub dim: Dim,
     pub cuts: usize,
     pub potions: usize,
-    pub monsters: usize,
+    /// Number of locked doors, each with its key
+    pub doors: usize,
+    /// The monsters of the level: guards are there from the start, the
+    /// other ones appear at the exit, in this order
+    pub monsters: Vec<MonsterKind>,
     pub lives: i32,
     pub status: &'static str,
-    pub disk: bool,
+    pub shape: Shape,
     pub fill: bool,
+    pub algorithm: Algorithm,
+    /// The seed of all random decisions, a random one is drawn
+    /// when building the maze if it's not set
+    pub seed: Option<u64>,
+    /// How far the player sees, the whole maze being visible when `None`
+    pub vision: Option<usize>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -43,6 +67,37 @@ impl SizeSpec {
     }
 }
 
+/// Return the kinds of the monsters of a level, new kinds being
+/// introduced as levels grow
+fn monster_kinds(
+    level: usize,
+    count: usize,
+) -> Vec<MonsterKind> {
+    let mut kinds = vec![MonsterKind::Hunter];
+    if level > 15 {
+        kinds.push(MonsterKind::Slow);
+    }
+    if level > 25 {
+        kinds.push(MonsterKind::Wanderer);
+    }
+    if level > 45 {
+        kinds.push(MonsterKind::Ambusher);
+    }
+    let mut monsters: Vec<MonsterKind> = (0..count)
+        .map(|i| kinds[(level + i) % kinds.len()])
+        .collect();
+    // guards come in addition to the other monsters
+    if level > 35 && level % 3 == 0 {
+        monsters.extend(std::iter::repeat(MonsterKind::Guard).take(1 + level / 100));
+    }
+    monsters
+}
+
+/// Return a new random seed, small enough to be easily shared
+pub fn random_seed() -> u64 {
+    thread_rng().gen::<u32>() as u64
+}
+
 /// Return a pseudo-pseudo-random number, capped and reproductible
 ///  (seed can be eg the level)
 fn twist(
@@ -56,6 +111,39 @@ fn twist(
 }
 
 impl Specs {
+    /// Change the shape, and the dimensions when they're imposed by the shape
+    pub fn set_shape(
+        &mut self,
+        shape: Shape,
+    ) {
+        if let Some(dim) = shape.natural_dim() {
+            self.dim = dim;
+        }
+        self.shape = shape;
+    }
+    /// The day of the daily challenge, in UTC so that it's the same
+    /// for everyone
+    pub fn daily_date() -> NaiveDate {
+        Utc::now().date_naive()
+    }
+    /// Specs of the daily challenge: a maze depending only on the date,
+    /// so that everyone plays the same one that day
+    pub fn for_daily(date: NaiveDate) -> Self {
+        let mut specs = Self::for_level(Self::daily_level(date));
+        specs.name = format!("Daily challenge of {date}");
+        specs.status = "The maze of the day, the same for everyone";
+        specs.seed = Some(Self::daily_seed(date));
+        specs
+    }
+    /// The level whose rules the daily challenge of a date follows
+    pub fn daily_level(date: NaiveDate) -> usize {
+        let mut rng = ChaCha8Rng::seed_from_u64(Self::daily_seed(date));
+        rng.gen_range(12..=40)
+    }
+    fn daily_seed(date: NaiveDate) -> u64 {
+        date.year() as u64 * 10_000 + date.month() as u64 * 100 + date.day() as u64
+    }
+    /// Specs of the maze of a game level, harder as the level grows
     pub fn for_level(level: usize) -> Self {
         let name = format!("Level {level}");
         let dim_spec = match level % 11 {
@@ -66,15 +154,43 @@ impl Specs {
             _ => SizeSpec::Normal,
         };
         let mut dim = dim_spec.dim(level);
-        let disk = level % 7 == 5;
-        if disk {
+        let shape = if level % 7 == 5 {
+            if level < 40 {
+                Shape::Disk
+            } else {
+                match (level / 7) % 5 {
+                    0 => Shape::Ring,
+                    1 => Shape::Diamond,
+                    2 => Shape::Heart,
+                    3 => Shape::Cross,
+                    _ => Shape::Disk,
+                }
+            }
+        } else {
+            Shape::Rectangle
+        };
+        if shape != Shape::Rectangle {
             dim.w = 24.max(dim.w);
             dim.h = 24.max(dim.h);
         } else if level % 13 == 7 {
             dim.verticalize();
         }
         let s = dim.w * dim.h;
-        let fill = !disk && !(level % 4 == 1 && level > 6);
+        let fill = !(shape != Shape::Rectangle || level % 4 == 1 && level > 6);
+        // levels which aren't filled (level % 4 == 1) keep the growing
+        // tree, the only algorithm able to stop before the end
+        let algorithm = if level < 12 {
+            Algorithm::GrowingTree
+        } else {
+            match level % 8 {
+                0 => Algorithm::Prim,
+                2 => Algorithm::Kruskal,
+                3 => Algorithm::Wilson,
+                4 => Algorithm::Eller,
+                6 => Algorithm::Division,
+                _ => Algorithm::GrowingTree,
+            }
+        };
 
         let lives;
         let potions;
@@ -150,12 +266,30 @@ impl Specs {
                 cuts = 1 + s / (60 + 2 * level);
             }
         }
+        // some levels are played in the dark
+        let vision = match level % 10 {
+            1 if level > 20 => Some(6),
+            7 if level > 40 => Some(9),
+            _ => None,
+        };
+        let monsters = monster_kinds(level, monsters);
+        let doors = if level > 12 && level % 6 == 3 {
+            1 + level / 60
+        } else {
+            0
+        };
         let status = match level {
-            1 => "Use arrow keys to move and exit the maze",
+            1 => MOVE_TUTORIAL,
             2 | 4 => "Red monsters teleport you",
             3 | 6 => "Pick lives on green squares",
-            5 | 8 | 12 => "You can abandon with key 'a'",
-            10 | 14 | 17 => "Hit 'w' to wait",
+            5 | 8 | 12 => GIVE_UP_TUTORIAL,
+            10 | 14 | 17 => WAIT_TUTORIAL,
+            21 => "It's dark, you only see what's around you",
+            15 => "Pick the yellow key to open the brown door",
+            16 => "Dark red monsters are slow",
+            26 => "Purple monsters wander until they see you",
+            36 => "Blue guards keep walking the same rounds",
+            46 => "Pink monsters try to cut your way",
             11 => "Sometimes there's no monster, just find the exit",
             _ => "",
         };
@@ -164,23 +298,42 @@ impl Specs {
             dim,
             cuts,
             potions,
+            doors,
             monsters,
             lives,
             status,
-            disk,
+            shape,
             fill,
+            algorithm,
+            seed: None,
+            vision,
         }
     }
-    pub fn for_terminal_build() -> std::io::Result<Self> {
-        let mut rng = thread_rng();
+    #[cfg(feature = "tui")]
+    pub fn for_terminal_build(
+        seed: Option<u64>,
+        algorithm: Option<Algorithm>,
+    ) -> std::io::Result<Self> {
+        Ok(Self::for_screen_build(Dim::terminal()?, seed, algorithm))
+    }
+    /// Specs of a random maze, either small or filling a screen
+    /// of the given size
+    pub fn for_screen_build(
+        screen: Dim,
+        seed: Option<u64>,
+        algorithm: Option<Algorithm>,
+    ) -> Self {
+        let seed = seed.unwrap_or_else(random_seed);
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
         let double = rng.gen_range(0..3) == 0;
         let dim = if double {
             Dim::new(rng.gen_range(8..35), rng.gen_range(7..20))
         } else {
-            let mut d = Dim::terminal()?;
-            d.w -= 2;
-            d.h = d.h * 2 - 3;
-            d
+            // small terminals still make playable mazes
+            Dim::new(
+                screen.w.saturating_sub(2).max(MIN_DIM),
+                (screen.h * 2).saturating_sub(3).max(MIN_DIM),
+            )
         };
         let cuts = match rng.gen_range(0..3) {
             0 => (dim.w * dim.h) / 2300,
@@ -188,16 +341,34 @@ impl Specs {
             _ => (dim.w * dim.h) / 60, // should be only 2
         };
         let fill = rng.gen_range(0..5) < 4;
-        Ok(Self {
+        Self {
             name: "random".to_string(),
             dim,
             cuts,
             potions: 0,
-            monsters: 0,
+            doors: 0,
+            monsters: Vec::new(),
             lives: 0,
             status: "",
-            disk: rng.gen_range(0..20) == 0,
+            shape: if rng.gen_range(0..20) == 0 {
+                Shape::Disk
+            } else {
+                Shape::Rectangle
+            },
             fill,
-        })
+            algorithm: algorithm.unwrap_or(Algorithm::GrowingTree),
+            seed: Some(seed),
+            vision: None,
+        }
     }
 }
+
+#[test]
+fn daily_challenges_depend_only_on_the_date() {
+    let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+    let a = Maze::from(Specs::for_daily(date));
+    let b = Maze::from(Specs::for_daily(date));
+    assert_eq!(MazeData::from(&a), MazeData::from(&b));
+    let next_day = date.succ_opt().unwrap();
+    assert_ne!(Specs::for_daily(date).seed, Specs::for_daily(next_day).seed);
+}
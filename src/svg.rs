@@ -0,0 +1,136 @@
+use {
+    crate::*,
+    std::io::Write,
+    termimad::{
+        coolor,
+        crossterm::style::Color,
+    },
+};
+
+/// Size of a maze cell, in SVG user units
+const CELL: usize = 10;
+
+/// Write the maze as a SVG document.
+///
+/// Invisible walls (outside of the shape) aren't drawn, so that the
+/// image has the outline of the maze.
+pub fn write_svg<W: Write>(
+    w: &mut W,
+    maze: &Maze,
+    skin: &Skin,
+) -> anyhow::Result<()> {
+    let Dim {
+        w: width,
+        h: height,
+    } = maze.dim;
+    writeln!(
+        w,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+        width * CELL,
+        height * CELL,
+        width * CELL,
+        height * CELL,
+    )?;
+    writeln!(
+        w,
+        "<title>{} - seed {}</title>",
+        escape(&maze.name),
+        maze.seed()
+    )?;
+    if let Some(room) = skin.room {
+        // paint the rooms, which are the only cells not covered by walls
+        write!(w, r#"<g fill="{}">"#, css_color(room))?;
+        write_runs(w, maze, |p| maze.is_room(p))?;
+        writeln!(w, "</g>")?;
+    }
+    write!(
+        w,
+        r#"<g fill="{}" shape-rendering="crispEdges">"#,
+        css_color(skin.wall)
+    )?;
+    write_runs(w, maze, |p| maze.visible_nature(p) == Nature::Wall)?;
+    writeln!(w, "</g>")?;
+    write!(w, r#"<g fill="{}">"#, css_color(skin.door))?;
+    write_runs(w, maze, |p| maze.visible_nature(p) == Nature::Door)?;
+    writeln!(w, "</g>")?;
+    // the solution, when highlighted, and the exit
+    write!(w, r#"<g fill="{}">"#, css_color(skin.highlight))?;
+    write_runs(w, maze, |p| {
+        maze.visible_nature(p) == Nature::Highlight || Some(p) == maze.exit()
+    })?;
+    writeln!(w, "</g>")?;
+    for y in 0..height {
+        for x in 0..width {
+            let p = Pos::new(x, y);
+            match maze.visible_nature(p) {
+                Nature::Player => write_disk(w, p, 0.45, skin.player)?,
+                Nature::Potion => write_disk(w, p, 0.3, skin.potion)?,
+                Nature::Key => write_disk(w, p, 0.3, skin.key)?,
+                Nature::Monster(kind) => write_disk(w, p, 0.4, skin.monster_color(kind))?,
+                _ => {}
+            }
+        }
+    }
+    writeln!(w, "</svg>")?;
+    Ok(())
+}
+
+/// Write, as rectangles, the horizontal runs of cells matching the filter
+fn write_runs<W: Write, F: Fn(Pos) -> bool>(
+    w: &mut W,
+    maze: &Maze,
+    filter: F,
+) -> anyhow::Result<()> {
+    for y in 0..maze.dim.h {
+        let mut x = 0;
+        while x < maze.dim.w {
+            if !filter(Pos::new(x, y)) {
+                x += 1;
+                continue;
+            }
+            let start = x;
+            while x < maze.dim.w && filter(Pos::new(x, y)) {
+                x += 1;
+            }
+            write!(
+                w,
+                r#"<rect x="{}" y="{}" width="{}" height="{}"/>"#,
+                start * CELL,
+                y * CELL,
+                (x - start) * CELL,
+                CELL,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Write a disk centered on the cell, its radius being a fraction of the cell size
+fn write_disk<W: Write>(
+    w: &mut W,
+    p: Pos,
+    radius: f32,
+    color: Color,
+) -> anyhow::Result<()> {
+    let half = CELL as f32 / 2.0;
+    writeln!(
+        w,
+        r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+        (p.x * CELL) as f32 + half,
+        (p.y * CELL) as f32 + half,
+        radius * CELL as f32,
+        css_color(color),
+    )?;
+    Ok(())
+}
+
+fn css_color(color: Color) -> String {
+    let rgb = coolor::Color::from(color).rgb();
+    format!("#{:02x}{:02x}{:02x}", rgb.r, rgb.g, rgb.b)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
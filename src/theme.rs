@@ -0,0 +1,136 @@
+use {
+    crate::*,
+    anyhow::{
+        anyhow,
+        bail,
+    },
+    std::{
+        env,
+        fs,
+    },
+    termimad::crossterm::style::Color,
+};
+
+/// The environment variable telling the theme when there's no `--theme`
+pub const THEME_ENV_VAR: &str = "MAZTER_THEME";
+
+/// The themes shipped with mazter, with their names
+pub static BUILT_IN_THEMES: &[(&str, &str)] = &[
+    ("dark", include_str!("../resources/themes/dark.toml")),
+    ("light", include_str!("../resources/themes/light.toml")),
+    (
+        "solarized",
+        include_str!("../resources/themes/solarized.toml"),
+    ),
+    (
+        "high-contrast",
+        include_str!("../resources/themes/high-contrast.toml"),
+    ),
+];
+
+/// Build the skin of the theme given either as argument or in the
+/// environment variable, as the name of a built-in theme or as the path
+/// to a file.
+///
+/// Without any, the `theme.toml` file of the config directory is used if it
+/// exists, and the default skin if it doesn't.
+pub fn load_skin(theme: Option<&str>) -> anyhow::Result<Skin> {
+    let theme = theme
+        .map(|s| s.to_string())
+        .or_else(|| env::var(THEME_ENV_VAR).ok());
+    let content = if let Some(theme) = theme {
+        match BUILT_IN_THEMES.iter().find(|(name, _)| *name == theme) {
+            Some((_, content)) => content.to_string(),
+            None => fs::read_to_string(&theme).map_err(|e| {
+                let names: Vec<&str> = BUILT_IN_THEMES.iter().map(|(name, _)| *name).collect();
+                anyhow!(
+                    "{theme:?} is neither a built-in theme ({}) nor a readable file: {e}",
+                    names.join(", ")
+                )
+            })?,
+        }
+    } else {
+        let path = config_dir()?.join("theme.toml");
+        if !path.exists() {
+            return Ok(Skin::build());
+        }
+        debug!("theme: {path:?}");
+        fs::read_to_string(path)?
+    };
+    skin_from_theme(&content)
+}
+
+/// Build a skin from a theme, which is a TOML file of `name = "color"` lines,
+/// the names being the ones of the skin's fields.
+///
+/// The colors which aren't given keep their default value.
+pub fn skin_from_theme(toml: &str) -> anyhow::Result<Skin> {
+    let entries = parse_theme(toml)?;
+    let is_set = |name: &str| entries.iter().any(|(n, _)| n == name);
+    let room = match entries.iter().find(|(name, _)| name == "room") {
+        Some((_, room)) => *room,
+        None => Skin::terminal_background(),
+    };
+    let mut skin = Skin::with_room(room);
+    for (name, color) in &entries {
+        skin.set_color(name, *color)?;
+    }
+    if !is_set("remembered") {
+        skin.remembered = Skin::fog(skin.room, skin.unknown);
+    }
+    if !is_set("visited") {
+        skin.visited = Skin::trail(skin.room, skin.player);
+    }
+    Ok(skin)
+}
+
+/// Read the colors of a theme
+fn parse_theme(toml: &str) -> anyhow::Result<Vec<(String, Option<Color>)>> {
+    let mut entries = Vec::new();
+    for entry in parse_flat_toml(toml).map_err(|e| anyhow!("Invalid theme: {e}"))? {
+        let color = parse_color(&entry.value).map_err(|e| anyhow!("{}: {e}", entry.name))?;
+        entries.push((entry.name, color));
+    }
+    Ok(entries)
+}
+
+/// Parse a color given by its crossterm name (eg `dark_red`), its ANSI
+/// value (eg `196`) or as RGB hex (eg `#ff0000`), `none` meaning no color
+pub fn parse_color(s: &str) -> anyhow::Result<Option<Color>> {
+    if s == "none" {
+        return Ok(None);
+    }
+    if let Ok(ansi) = s.parse::<u8>() {
+        return Ok(Some(Color::AnsiValue(ansi)));
+    }
+    if let Some(hex) = s.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            bail!("Invalid RGB color: {s:?}");
+        }
+        let channel = |i: usize| {
+            u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| anyhow!("Invalid RGB color: {s:?}"))
+        };
+        return Ok(Some(Color::Rgb {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        }));
+    }
+    Color::try_from(s)
+        .map(Some)
+        .map_err(|_| anyhow!("Unknown color: {s:?}"))
+}
+
+#[test]
+fn built_in_themes_are_valid() {
+    for (name, content) in BUILT_IN_THEMES {
+        let entries = parse_theme(content).unwrap();
+        assert!(entries.len() > 10, "theme {name} is incomplete");
+        skin_from_theme(content).unwrap();
+    }
+    assert!(parse_theme("wall = \"#00ff0\"").is_err());
+    assert!(parse_theme("wall = \"grey\" # a comment").is_ok());
+    assert!(parse_theme("wall = 102").is_ok());
+    assert!(parse_theme("wall = grey").is_err());
+    assert!(parse_theme("[colors]\nwall = \"grey\"").is_err());
+}